@@ -0,0 +1,205 @@
+use std::env::VarError;
+use std::fmt;
+use std::path::Path;
+
+use reqwest::header::HeaderValue;
+use serde::{Deserialize, Deserializer};
+use zeroize::Zeroizing;
+
+use crate::redact::REDACTED;
+use crate::AbacatePayError;
+
+/// Environment variable read by [`ApiKey::from_env`] and `AbacatePay::from_env`.
+pub const API_KEY_ENV: &str = "ABACATEPAY_API_KEY";
+
+/// Which AbacatePay environment a key belongs to, as told by its prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMode {
+    /// A sandbox key (`abc_dev_...`): charges are fake and payments can be simulated.
+    Dev,
+    /// A live key (`abc_prod_...`).
+    Production,
+    /// The key has neither known prefix.
+    Unknown,
+}
+
+/// An API key whose memory is wiped on drop and which never appears in `Debug` or `Display`.
+///
+/// Use [`expose_secret`](ApiKey::expose_secret) to read the key itself.
+///
+/// Only this value is wiped: each request copies the key into an `Authorization` header,
+/// and the HTTP stack frees those buffers without zeroing them.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(Zeroizing<String>);
+
+impl ApiKey {
+    pub fn new(key: String) -> Self {
+        Self(Zeroizing::new(key))
+    }
+
+    /// Reads the key from [`API_KEY_ENV`].
+    pub fn from_env() -> Result<Self, AbacatePayError> {
+        Self::from_env_var(API_KEY_ENV)
+    }
+
+    /// Reads the key from the environment variable `name`.
+    pub fn from_env_var(name: &str) -> Result<Self, AbacatePayError> {
+        // `VarError::NotUnicode` displays the raw value, so it must not end up in the message.
+        let key = std::env::var(name).map_err(|e| {
+            AbacatePayError::Config(match e {
+                VarError::NotPresent => format!("{name} is not set"),
+                VarError::NotUnicode(_) => format!("{name} is not valid UTF-8"),
+            })
+        })?;
+        Self::parse(Zeroizing::new(key))
+            .ok_or_else(|| AbacatePayError::Config(format!("{name} is empty")))
+    }
+
+    /// Reads the key from a file holding only the key, e.g. a mounted container secret.
+    ///
+    /// Surrounding whitespace, such as a trailing newline, is ignored.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AbacatePayError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map(Zeroizing::new)
+            .map_err(|e| {
+                AbacatePayError::Config(format!("could not read {}: {e}", path.display()))
+            })?;
+        Self::parse(contents)
+            .ok_or_else(|| AbacatePayError::Config(format!("{} is empty", path.display())))
+    }
+
+    fn parse(raw: Zeroizing<String>) -> Option<Self> {
+        let key = raw.trim();
+        (!key.is_empty()).then(|| Self::new(key.to_string()))
+    }
+
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// The `Authorization` header for this key, marked sensitive so `Debug` output of
+    /// requests leaves it out. `None` if the key is not a valid header value.
+    ///
+    /// The returned header is not zeroized; keep it only as long as the request.
+    pub(crate) fn authorization_header(&self) -> Option<HeaderValue> {
+        let bearer = Zeroizing::new(format!("Bearer {}", self.expose_secret()));
+        let mut value = HeaderValue::from_str(&bearer).ok()?;
+        value.set_sensitive(true);
+        Some(value)
+    }
+
+    pub fn mode(&self) -> KeyMode {
+        if self.0.starts_with("abc_dev_") {
+            KeyMode::Dev
+        } else if self.0.starts_with("abc_prod_") {
+            KeyMode::Production
+        } else {
+            KeyMode::Unknown
+        }
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ApiKey").field(&REDACTED).finish()
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        Self::new(key.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_prints_the_key() {
+        let key = ApiKey::from("abc_dev_secret");
+
+        assert_eq!(format!("{key}"), "[REDACTED]");
+        assert_eq!(format!("{key:?}"), "ApiKey(\"[REDACTED]\")");
+        assert_eq!(key.expose_secret(), "abc_dev_secret");
+    }
+
+    #[test]
+    fn mode_from_prefix() {
+        assert_eq!(ApiKey::from("abc_dev_123").mode(), KeyMode::Dev);
+        assert_eq!(ApiKey::from("abc_prod_123").mode(), KeyMode::Production);
+        assert_eq!(ApiKey::from("123").mode(), KeyMode::Unknown);
+    }
+
+    #[test]
+    fn reads_trimmed_key_from_file() {
+        let path = std::env::temp_dir().join(format!("abacatepay-key-{}", std::process::id()));
+        std::fs::write(&path, "abc_dev_secret\n").unwrap();
+
+        let key = ApiKey::from_file(&path);
+        std::fs::write(&path, "  \n").unwrap();
+        let empty = ApiKey::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(key.unwrap().expose_secret(), "abc_dev_secret");
+        assert!(matches!(empty, Err(AbacatePayError::Config(_))));
+        assert!(matches!(
+            ApiKey::from_file(&path),
+            Err(AbacatePayError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn missing_env_var() {
+        assert!(matches!(
+            ApiKey::from_env_var("ABACATEPAY_TEST_UNSET_KEY"),
+            Err(AbacatePayError::Config(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_unicode_env_var_is_not_echoed() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        std::env::set_var(
+            "ABACATEPAY_TEST_NON_UNICODE_KEY",
+            OsStr::from_bytes(b"abc_dev_secret\xff"),
+        );
+        let error = ApiKey::from_env_var("ABACATEPAY_TEST_NON_UNICODE_KEY").unwrap_err();
+
+        assert!(!error.to_string().contains("secret"), "{error}");
+    }
+
+    #[test]
+    fn authorization_header_is_sensitive() {
+        let header = ApiKey::from("abc_dev_secret")
+            .authorization_header()
+            .unwrap();
+
+        assert!(header.is_sensitive());
+        assert_eq!(header, "Bearer abc_dev_secret");
+        assert!(!format!("{header:?}").contains("secret"));
+        assert_eq!(ApiKey::from("abc\ndev").authorization_header(), None);
+    }
+}
@@ -0,0 +1,320 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use abacatepay_rust_sdk::billing::{
+    Billing, BillingKind, BillingMethods, BillingStatus, CreateBillingProduct,
+};
+use abacatepay_rust_sdk::export::{self, Column, ExportFormat, ExportOptions};
+use abacatepay_rust_sdk::ids::{BillingId, CustomerId};
+use abacatepay_rust_sdk::AbacatePay;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use clap::{Args, Subcommand};
+
+use crate::output::{format_reais, label, print_json, Table};
+use crate::CliError;
+
+#[derive(Debug, Subcommand)]
+pub enum BillingCommand {
+    /// Create a billing and print its checkout URL
+    Create(CreateArgs),
+    /// List billings
+    List(ListArgs),
+    /// Show a single billing
+    Get { id: String },
+    /// Write billings to a CSV or JSON Lines file
+    Export(ExportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CreateArgs {
+    /// Product as `EXTERNAL_ID,NAME,QUANTITY,PRICE[,DESCRIPTION]`; repeat for several
+    #[arg(long = "product", required = true, value_parser = parse_product)]
+    products: Vec<CreateBillingProduct>,
+    /// Payment method (`pix` or `card`); repeat to accept several
+    #[arg(long = "method", default_value = "pix", value_parser = parse_method)]
+    methods: Vec<BillingMethods>,
+    /// `one-time` or `multiple-payments`
+    #[arg(long, default_value = "one-time", value_parser = parse_frequency)]
+    frequency: BillingKind,
+    #[arg(long)]
+    return_url: String,
+    #[arg(long)]
+    completion_url: String,
+    #[arg(long)]
+    customer_id: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only billings in this status (e.g. `paid`); repeat to accept several
+    #[arg(long = "status", value_parser = parse_status)]
+    statuses: Vec<BillingStatus>,
+    /// Only billings of this customer
+    #[arg(long)]
+    customer_id: Option<String>,
+    /// `one-time` or `multiple-payments`
+    #[arg(long, value_parser = parse_frequency)]
+    frequency: Option<BillingKind>,
+    /// Maximum number of billings to show
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// `csv` or `jsonl`
+    #[arg(long, default_value = "csv")]
+    format: ExportFormat,
+    /// Columns to include, comma-separated (e.g. `id,status,amount,customer_email`)
+    #[arg(long = "columns", value_delimiter = ',')]
+    columns: Vec<Column>,
+    /// Only billings created on or after this date (`YYYY-MM-DD` or RFC 3339)
+    #[arg(long, value_parser = parse_from)]
+    from: Option<DateTime<Utc>>,
+    /// Only billings created up to this date, inclusive (`YYYY-MM-DD` or RFC 3339)
+    #[arg(long, value_parser = parse_until)]
+    until: Option<DateTime<Utc>>,
+    /// Only billings in this status (e.g. `paid`); repeat to accept several
+    #[arg(long = "status", value_parser = parse_status)]
+    statuses: Vec<BillingStatus>,
+    /// File to write; defaults to standard output
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+pub async fn run(client: &AbacatePay, command: BillingCommand, json: bool) -> Result<(), CliError> {
+    match command {
+        BillingCommand::Create(args) => {
+            let mut products = args.products.into_iter();
+            let mut methods = args.methods.into_iter();
+            let (Some(product), Some(method)) = (products.next(), methods.next()) else {
+                return Err("at least one product and one method are required".into());
+            };
+
+            let mut builder = client
+                .create_billing()
+                .frequency(args.frequency)
+                .product(product)
+                .method(method)
+                .return_url(args.return_url)
+                .completion_url(args.completion_url);
+            for product in products {
+                builder = builder.product(product);
+            }
+            for method in methods {
+                builder = builder.method(method);
+            }
+            if let Some(customer_id) = args.customer_id {
+                builder = builder.customer_id(CustomerId::from(customer_id));
+            }
+
+            let billing = builder.build().await?;
+            print_billings(&[billing], json)
+        }
+        BillingCommand::List(args) => {
+            let mut query = client.query_billings();
+            for status in args.statuses {
+                query = query.status(status);
+            }
+            if let Some(customer_id) = args.customer_id {
+                query = query.customer_id(CustomerId::from(customer_id));
+            }
+            if let Some(frequency) = args.frequency {
+                query = query.frequency(frequency);
+            }
+            if let Some(limit) = args.limit {
+                query = query.limit(limit);
+            }
+
+            let billings = query.build().await?;
+            print_billings(&billings, json)
+        }
+        BillingCommand::Get { id } => {
+            let billing = client.get_billing(BillingId::from(id)).await?;
+            print_billings(&[billing], json)
+        }
+        BillingCommand::Export(args) => {
+            let mut query = client.query_billings();
+            for status in args.statuses {
+                query = query.status(status);
+            }
+            if let Some(from) = args.from {
+                query = query.created_from(from);
+            }
+            if let Some(until) = args.until {
+                query = query.created_until(until);
+            }
+
+            let writer: Box<dyn Write> = match &args.output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(io::stdout().lock()),
+            };
+            let options = ExportOptions {
+                format: args.format,
+                columns: args.columns,
+            };
+            let count = export::write_billings(query.into_stream(), writer, &options).await?;
+            if let Some(path) = &args.output {
+                eprintln!("Exported {count} billings to {}", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_billings(billings: &[Billing], json: bool) -> Result<(), CliError> {
+    if json {
+        return Ok(print_json(&billings)?);
+    }
+
+    let mut table = Table::new(vec![
+        "ID",
+        "STATUS",
+        "AMOUNT",
+        "FREQUENCY",
+        "METHODS",
+        "CREATED",
+        "URL",
+    ]);
+    for billing in billings {
+        table.row(vec![
+            billing.id.to_string(),
+            label(&billing.status),
+            format_reais(billing.amount),
+            label(&billing.frequency),
+            billing
+                .methods
+                .iter()
+                .map(label)
+                .collect::<Vec<_>>()
+                .join(","),
+            billing.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            billing.url.clone(),
+        ]);
+    }
+    table.print();
+    Ok(())
+}
+
+pub fn parse_status(value: &str) -> Result<BillingStatus, String> {
+    let value = value.to_ascii_uppercase();
+    serde_json::from_value(serde_json::Value::String(value)).map_err(|e| e.to_string())
+}
+
+fn parse_from(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_date(value, 0)
+}
+
+/// A bare date covers the whole day, so the bound becomes the following midnight.
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_date(value, 1)
+}
+
+fn parse_date(value: &str, day_offset: u64) -> Result<DateTime<Utc>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.checked_add_days(Days::new(day_offset)))
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+        .ok_or_else(|| format!("invalid date `{value}`, expected `YYYY-MM-DD` or RFC 3339"))
+}
+
+pub fn parse_frequency(value: &str) -> Result<BillingKind, String> {
+    match value.to_ascii_lowercase().replace('_', "-").as_str() {
+        "one-time" => Ok(BillingKind::OneTime),
+        "multiple-payments" => Ok(BillingKind::MultiplePayments),
+        other => Err(format!(
+            "unknown frequency `{other}`, expected `one-time` or `multiple-payments`"
+        )),
+    }
+}
+
+fn parse_method(value: &str) -> Result<BillingMethods, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "pix" => Ok(BillingMethods::Pix),
+        "card" => Ok(BillingMethods::Card),
+        other => Err(format!(
+            "unknown method `{other}`, expected `pix` or `card`"
+        )),
+    }
+}
+
+fn parse_product(value: &str) -> Result<CreateBillingProduct, String> {
+    let parts: Vec<&str> = value.splitn(5, ',').map(str::trim).collect();
+    let [external_id, name, quantity, price, rest @ ..] = parts.as_slice() else {
+        return Err("expected `EXTERNAL_ID,NAME,QUANTITY,PRICE[,DESCRIPTION]`".to_string());
+    };
+
+    Ok(CreateBillingProduct {
+        external_id: external_id.to_string(),
+        name: name.to_string(),
+        quantity: quantity
+            .parse()
+            .map_err(|_| format!("invalid quantity `{quantity}`"))?,
+        price: price
+            .parse()
+            .map_err(|_| format!("invalid price `{price}`"))?,
+        description: rest.first().map(|description| description.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_argument() {
+        let product = parse_product("sku-1, Camiseta, 2, 59.9, Azul, tamanho M").unwrap();
+
+        assert_eq!(
+            product,
+            CreateBillingProduct {
+                external_id: "sku-1".to_string(),
+                name: "Camiseta".to_string(),
+                quantity: 2,
+                price: 59.9,
+                description: Some("Azul, tamanho M".to_string()),
+            }
+        );
+        assert!(parse_product("sku-1,Camiseta,two,59.9").is_err());
+        assert!(parse_product("sku-1,Camiseta").is_err());
+    }
+
+    #[test]
+    fn enum_arguments() {
+        assert_eq!(parse_status("paid").unwrap(), BillingStatus::PAID);
+        assert_eq!(
+            parse_status("disputed").unwrap(),
+            BillingStatus::Unknown("DISPUTED".to_string())
+        );
+        assert_eq!(
+            parse_frequency("MULTIPLE_PAYMENTS").unwrap(),
+            BillingKind::MultiplePayments
+        );
+        assert_eq!(parse_method("Card").unwrap(), BillingMethods::Card);
+        assert!(parse_method("boleto").is_err());
+    }
+
+    #[test]
+    fn date_arguments() {
+        assert_eq!(
+            parse_from("2024-11-01").unwrap().to_rfc3339(),
+            "2024-11-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_until("2024-11-30").unwrap().to_rfc3339(),
+            "2024-12-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_until("2024-11-30T12:00:00-03:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-11-30T15:00:00+00:00"
+        );
+        assert!(parse_from("30/11/2024").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! API key lookup: `--api-key` / `ABACATEPAY_API_KEY` first, then a profile in the config
+//! file (`$ABACATEPAY_CONFIG`, or `abacatepay/config.toml` in the user config directory):
+//!
+//! ```toml
+//! [profiles.default]
+//! api_key = "abc_dev_..."
+//!
+//! [profiles.production]
+//! api_key = "abc_prod_..."
+//! base_url = "https://api.abacatepay.com/v1"
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use abacatepay_rust_sdk::ApiKey;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Profile {
+    pub api_key: ApiKey,
+    pub base_url: Option<String>,
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    match std::env::var_os("ABACATEPAY_CONFIG") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => dirs::config_dir().map(|dir| dir.join("abacatepay").join("config.toml")),
+    }
+}
+
+/// Picks the key given on the command line or environment, falling back to `profile`.
+pub fn resolve(
+    api_key: Option<ApiKey>,
+    profile: &str,
+    path: Option<&Path>,
+) -> Result<Profile, String> {
+    if let Some(api_key) = api_key {
+        return Ok(Profile {
+            api_key,
+            base_url: None,
+        });
+    }
+
+    let path = path.ok_or("no API key given and no config directory found")?;
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        format!(
+            "no API key given (use --api-key or ABACATEPAY_API_KEY) and {} could not be read: {e}",
+            path.display()
+        )
+    })?;
+    parse_profile(&contents, profile).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn parse_profile(contents: &str, profile: &str) -> Result<Profile, String> {
+    let config: ConfigFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+    config
+        .profiles
+        .get(profile)
+        .cloned()
+        .ok_or_else(|| format!("profile `{profile}` not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [profiles.default]
+        api_key = "dev-key"
+
+        [profiles.production]
+        api_key = "prod-key"
+        base_url = "https://proxy.example.com/v1"
+    "#;
+
+    #[test]
+    fn explicit_key_wins() {
+        let profile = resolve(Some(ApiKey::from("cli-key")), "default", None).unwrap();
+
+        assert_eq!(profile.api_key.expose_secret(), "cli-key");
+    }
+
+    #[test]
+    fn reads_named_profile() {
+        let profile = parse_profile(CONFIG, "production").unwrap();
+
+        assert_eq!(
+            profile,
+            Profile {
+                api_key: ApiKey::from("prod-key"),
+                base_url: Some("https://proxy.example.com/v1".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_profile(CONFIG, "default")
+                .unwrap()
+                .api_key
+                .expose_secret(),
+            "dev-key"
+        );
+        assert!(parse_profile(CONFIG, "staging").is_err());
+    }
+}
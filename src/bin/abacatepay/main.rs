@@ -0,0 +1,123 @@
+//! `abacatepay`: command-line access to the AbacatePay API for support and testing.
+
+mod billing;
+mod config;
+mod output;
+mod pix;
+mod webhook;
+
+use std::process::ExitCode;
+
+use abacatepay_rust_sdk::{AbacatePay, AbacatePayError, ApiKey};
+use clap::{Parser, Subcommand};
+
+use crate::billing::BillingCommand;
+use crate::pix::PixCommand;
+use crate::webhook::WebhookCommand;
+
+pub type CliError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "abacatepay",
+    version,
+    about = "Command-line client for the AbacatePay API"
+)]
+struct Cli {
+    /// API key; overrides the profile file
+    #[arg(
+        long,
+        global = true,
+        env = "ABACATEPAY_API_KEY",
+        hide_env_values = true
+    )]
+    api_key: Option<ApiKey>,
+
+    /// Profile to read from the config file
+    #[arg(
+        long,
+        global = true,
+        env = "ABACATEPAY_PROFILE",
+        default_value = "default"
+    )]
+    profile: String,
+
+    /// Print machine-readable JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create, list and inspect billings
+    #[command(subcommand)]
+    Billing(BillingCommand),
+    /// Create, check, simulate and watch PIX QR code charges
+    #[command(subcommand)]
+    Pix(PixCommand),
+    /// Receive webhook deliveries locally
+    #[command(subcommand)]
+    Webhook(WebhookCommand),
+}
+
+fn client(api_key: Option<ApiKey>, profile: &str) -> Result<AbacatePay, CliError> {
+    let path = config::config_path();
+    let profile = config::resolve(api_key, profile, path.as_deref())?;
+
+    let client = AbacatePay::new(profile.api_key);
+    Ok(match profile.base_url {
+        Some(base_url) => client.with_base_url(base_url),
+        None => client,
+    })
+}
+
+async fn run(cli: Cli) -> Result<(), CliError> {
+    let Cli {
+        api_key,
+        profile,
+        json,
+        command,
+    } = cli;
+
+    match command {
+        Command::Billing(command) => billing::run(&client(api_key, &profile)?, command, json).await,
+        Command::Pix(command) => pix::run(&client(api_key, &profile)?, command, json).await,
+        Command::Webhook(command) => webhook::run(command, json).await,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            if let Some(request_id) = e
+                .downcast_ref::<AbacatePayError>()
+                .and_then(AbacatePayError::request_id)
+            {
+                eprintln!("request id: {request_id}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn command_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+
+/// Formats an amount in cents as reais, e.g. `R$ 1234.50`.
+pub fn format_reais(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{sign}R$ {}.{:02}", cents / 100, cents % 100)
+}
+
+/// The wire name of an enum value, e.g. `PAID` or `ONE_TIME`.
+pub fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(label)) => label,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), serde_json::Error> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// A plain text table with left-aligned columns sized to their widest cell.
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut out = line(self.headers.clone());
+        for row in &self.rows {
+            out.push('\n');
+            out.push_str(&line(row.iter().map(String::as_str).collect()));
+        }
+        out
+    }
+
+    pub fn print(&self) {
+        println!("{}", self.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reais() {
+        assert_eq!(format_reais(0), "R$ 0.00");
+        assert_eq!(format_reais(123450), "R$ 1234.50");
+        assert_eq!(format_reais(-5), "-R$ 0.05");
+    }
+
+    #[test]
+    fn table_alignment() {
+        let mut table = Table::new(vec!["ID", "STATUS"]);
+        table.row(vec!["bill_1".to_string(), "PAID".to_string()]);
+        table.row(vec!["b2".to_string(), "PENDING".to_string()]);
+
+        assert_eq!(
+            table.render(),
+            "ID      STATUS\nbill_1  PAID\nb2      PENDING"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use abacatepay_rust_sdk::ids::PixChargeId;
+use abacatepay_rust_sdk::pix_charge::{CheckPixStatusData, PixChargeData};
+use abacatepay_rust_sdk::{AbacatePay, PollOptions};
+use clap::Subcommand;
+use futures::StreamExt;
+
+use crate::output::{format_reais, label, print_json, Table};
+use crate::CliError;
+
+#[derive(Debug, Subcommand)]
+pub enum PixCommand {
+    /// Create a PIX QR code charge and print its copy-and-paste code
+    Create {
+        /// Amount in cents
+        #[arg(long)]
+        amount: i64,
+        /// Expiration in seconds
+        #[arg(long)]
+        expires_in: Option<u64>,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Show the current status of a charge
+    Status { id: String },
+    /// Simulate the payment of a charge (dev mode only)
+    Simulate { id: String },
+    /// Print every status change of a charge until it finishes
+    Watch {
+        id: String,
+        /// Seconds between the first status checks
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+}
+
+pub async fn run(client: &AbacatePay, command: PixCommand, json: bool) -> Result<(), CliError> {
+    match command {
+        PixCommand::Create {
+            amount,
+            expires_in,
+            description,
+        } => {
+            let charge = client
+                .create_pix_charge()
+                .amount(amount)
+                .expires_in(expires_in)
+                .description(description)
+                .build()
+                .await?;
+            print_charge(&charge, json)
+        }
+        PixCommand::Status { id } => {
+            let status = client
+                .check_pix_status(PixChargeId::from(id))
+                .build()
+                .await?;
+            print_status(&status, json)
+        }
+        PixCommand::Simulate { id } => {
+            let charge = client
+                .create_simulate_pix_payment(PixChargeId::from(id))
+                .build()
+                .await?;
+            print_charge(&charge, json)
+        }
+        PixCommand::Watch { id, interval } => {
+            let options = PollOptions {
+                initial_interval: Duration::from_secs(interval),
+                ..PollOptions::default()
+            };
+            let updates = client.watch_pix_charge(PixChargeId::from(id), options);
+            futures::pin_mut!(updates);
+            while let Some(update) = updates.next().await {
+                print_status(&update?, json)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_charge(charge: &PixChargeData, json: bool) -> Result<(), CliError> {
+    if json {
+        return Ok(print_json(charge)?);
+    }
+
+    let mut table = Table::new(vec!["ID", "STATUS", "AMOUNT", "EXPIRES", "DEV"]);
+    table.row(vec![
+        charge.id.to_string(),
+        label(&charge.status),
+        format_reais(charge.amount),
+        charge.expires_at.clone(),
+        charge.dev_mode.to_string(),
+    ]);
+    table.print();
+    println!("\n{}", charge.br_code);
+    Ok(())
+}
+
+fn print_status(status: &CheckPixStatusData, json: bool) -> Result<(), CliError> {
+    if json {
+        println!("{}", serde_json::to_string(status)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}  {}  (expires {})",
+        chrono::Local::now().format("%H:%M:%S"),
+        label(&status.status),
+        status.expires_at.format("%Y-%m-%d %H:%M")
+    );
+    Ok(())
+}
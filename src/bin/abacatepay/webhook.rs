@@ -0,0 +1,217 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use abacatepay_rust_sdk::webhook::{self, WebhookEvent, WebhookEventKind, SIGNATURE_HEADER};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use clap::Subcommand;
+use tokio::net::TcpListener;
+
+use crate::output::{format_reais, label};
+use crate::CliError;
+
+#[derive(Debug, Subcommand)]
+pub enum WebhookCommand {
+    /// Accept webhook deliveries on a local port, verify and print them
+    Listen {
+        #[arg(long, default_value_t = 8787)]
+        port: u16,
+        /// Secret the deliveries are signed with
+        #[arg(long, env = "ABACATEPAY_WEBHOOK_SECRET", hide_env_values = true)]
+        secret: String,
+        /// Forward verified deliveries to this URL, e.g. your local application
+        #[arg(long)]
+        forward_to: Option<String>,
+    },
+}
+
+struct Listener {
+    secret: String,
+    forward_to: Option<String>,
+    http: reqwest::Client,
+    json: bool,
+}
+
+pub async fn run(command: WebhookCommand, json: bool) -> Result<(), CliError> {
+    match command {
+        WebhookCommand::Listen {
+            port,
+            secret,
+            forward_to,
+        } => {
+            let socket = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port))).await?;
+            eprintln!("Listening for webhooks on http://{}", socket.local_addr()?);
+            if let Some(url) = &forward_to {
+                eprintln!("Forwarding verified deliveries to {url}");
+            }
+
+            let listener = Listener {
+                secret,
+                forward_to,
+                http: reqwest::Client::new(),
+                json,
+            };
+            axum::serve(socket, router(listener)).await?;
+            Ok(())
+        }
+    }
+}
+
+fn router(listener: Listener) -> Router {
+    Router::new()
+        .fallback(post(receive))
+        .with_state(Arc::new(listener))
+}
+
+async fn receive(
+    State(listener): State<Arc<Listener>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let Some(signature) = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        eprintln!("rejected delivery: missing {SIGNATURE_HEADER} header");
+        return StatusCode::UNAUTHORIZED;
+    };
+    if let Err(e) = webhook::verify_signature(&body, signature, &listener.secret) {
+        eprintln!("rejected delivery: {e}");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    match WebhookEvent::parse(&body) {
+        Ok(event) if !listener.json => print_event(&event),
+        Ok(_) => println!("{}", String::from_utf8_lossy(&body)),
+        Err(e) => {
+            eprintln!("rejected delivery: {e}");
+            return StatusCode::BAD_REQUEST;
+        }
+    }
+
+    let Some(url) = &listener.forward_to else {
+        return StatusCode::OK;
+    };
+    let forwarded = listener
+        .http
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .body(body)
+        .send()
+        .await;
+    match forwarded {
+        Ok(response) => {
+            eprintln!("forwarded to {url}: {}", response.status());
+            StatusCode::from_u16(response.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY)
+        }
+        Err(e) => {
+            eprintln!("failed to forward to {url}: {e}");
+            StatusCode::BAD_GATEWAY
+        }
+    }
+}
+
+fn print_event(event: &WebhookEvent) {
+    let mode = if event.dev_mode { "dev" } else { "prod" };
+    println!(
+        "{}  {}  {}  ({mode})",
+        chrono::Local::now().format("%H:%M:%S"),
+        event.kind.name(),
+        event.id
+    );
+
+    match &event.kind {
+        WebhookEventKind::BillingPaid(data) => {
+            println!(
+                "  payment  {} via {} (fee {})",
+                format_reais(data.payment.amount),
+                data.payment.method,
+                format_reais(data.payment.fee)
+            );
+            if let Some(billing) = &data.billing {
+                println!("  billing  {}  {}", billing.id, label(&billing.status));
+            }
+            if let Some(charge) = &data.pix_qr_code {
+                println!("  pix      {}  {}", charge.id, label(&charge.status));
+            }
+        }
+        WebhookEventKind::WithdrawDone(data) | WebhookEventKind::WithdrawFailed(data) => {
+            println!(
+                "  withdraw {}  {}  {}",
+                data.transaction.id,
+                data.transaction.status,
+                format_reais(data.transaction.amount)
+            );
+        }
+        WebhookEventKind::Unknown { data, .. } => println!("  {data}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header as header_eq, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &str = r#"{"id":"log_1","event":"withdraw.done","devMode":true,"data":{"transaction":{"id":"tran_1","status":"COMPLETE","amount":500}}}"#;
+
+    async fn spawn(forward_to: Option<String>) -> String {
+        let socket = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let listener = Listener {
+            secret: SECRET.to_string(),
+            forward_to,
+            http: reqwest::Client::new(),
+            json: true,
+        };
+        tokio::spawn(async move { axum::serve(socket, router(listener)).await });
+        format!("http://{addr}/webhooks")
+    }
+
+    async fn deliver(url: &str, signature: &str) -> u16 {
+        reqwest::Client::new()
+            .post(url)
+            .header(SIGNATURE_HEADER, signature)
+            .body(BODY)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_signatures() {
+        let url = spawn(None).await;
+
+        assert_eq!(
+            deliver(&url, &webhook::sign(BODY.as_bytes(), "other")).await,
+            401
+        );
+        assert_eq!(
+            deliver(&url, &webhook::sign(BODY.as_bytes(), SECRET)).await,
+            200
+        );
+    }
+
+    #[tokio::test]
+    async fn forwards_verified_deliveries() {
+        let app = MockServer::start().await;
+        let signature = webhook::sign(BODY.as_bytes(), SECRET);
+        Mock::given(method("POST"))
+            .and(header_eq(SIGNATURE_HEADER, signature.as_str()))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&app)
+            .await;
+
+        let url = spawn(Some(app.uri())).await;
+
+        assert_eq!(deliver(&url, &signature).await, 204);
+    }
+}
@@ -0,0 +1,642 @@
+//! Parsing and generation of PIX BR Codes ("copia e cola"), the EMV-QR payloads found in
+//! `PixChargeData::br_code`.
+//!
+//! A BR Code is a sequence of TLV fields: a two digit id, a two digit length and the value.
+//! Some fields (merchant account information, additional data) nest further TLV fields.
+//! The last field, `63`, holds a CRC16-CCITT checksum of everything before its value.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+const PAYLOAD_FORMAT_INDICATOR: &str = "00";
+const POINT_OF_INITIATION_METHOD: &str = "01";
+const MERCHANT_ACCOUNT_INFORMATION: &str = "26";
+const MERCHANT_CATEGORY_CODE: &str = "52";
+const TRANSACTION_CURRENCY: &str = "53";
+const TRANSACTION_AMOUNT: &str = "54";
+const COUNTRY_CODE: &str = "58";
+const MERCHANT_NAME: &str = "59";
+const MERCHANT_CITY: &str = "60";
+const POSTAL_CODE: &str = "61";
+const ADDITIONAL_DATA: &str = "62";
+const CRC: &str = "63";
+
+const PIX_GUI: &str = "br.gov.bcb.pix";
+const PIX_GUI_ID: &str = "00";
+const PIX_KEY: &str = "01";
+const PIX_DESCRIPTION: &str = "02";
+const PIX_URL: &str = "25";
+const ADDITIONAL_DATA_TXID: &str = "05";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BrCodeError {
+    #[error("BR Code is truncated at position {position}")]
+    Truncated { position: usize },
+
+    #[error("Invalid field header at position {position}")]
+    InvalidHeader { position: usize },
+
+    #[error("Missing required field {id}")]
+    MissingField { id: &'static str },
+
+    #[error("Invalid value for field {id}: {reason}")]
+    InvalidField { id: String, reason: String },
+
+    #[error("BR Code does not contain PIX merchant account information")]
+    NotPix,
+
+    #[error("CRC mismatch: code has {found:04X}, computed {computed:04X}")]
+    CrcMismatch { found: u16, computed: u16 },
+}
+
+/// Whether a BR Code may be paid once (dynamic) or many times (static).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointOfInitiation {
+    Static,
+    Dynamic,
+}
+
+/// The PIX entry (`26`) of the merchant account information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PixMerchantAccount {
+    /// PIX key of the receiver, present in static codes.
+    pub key: Option<String>,
+    /// Free text shown to the payer.
+    pub description: Option<String>,
+    /// Location of the charge payload, present in dynamic codes.
+    pub url: Option<String>,
+}
+
+/// A parsed and checksum-verified PIX BR Code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrCode {
+    pub payload_format_indicator: String,
+    pub point_of_initiation: Option<PointOfInitiation>,
+    pub merchant_account: PixMerchantAccount,
+    pub merchant_category_code: String,
+    /// ISO 4217 numeric currency code, `986` for BRL.
+    pub currency: String,
+    /// Amount in cents, absent when the payer chooses it.
+    pub amount: Option<i64>,
+    pub country_code: String,
+    pub merchant_name: String,
+    pub merchant_city: String,
+    pub postal_code: Option<String>,
+    /// Transaction id from the additional data field, `***` when not set.
+    pub txid: Option<String>,
+    pub crc: u16,
+}
+
+impl BrCode {
+    pub fn parse(code: &str) -> Result<Self, BrCodeError> {
+        let code = code.trim();
+        let fields = parse_tlv(code)?;
+
+        let (crc_field, rest) = fields
+            .split_last()
+            .ok_or(BrCodeError::Truncated { position: 0 })?;
+        if crc_field.id != CRC {
+            return Err(BrCodeError::MissingField { id: CRC });
+        }
+        let found = u16::from_str_radix(crc_field.value, 16)
+            .ok()
+            .filter(|_| crc_field.value.len() == 4)
+            .ok_or_else(|| BrCodeError::InvalidField {
+                id: CRC.to_string(),
+                reason: "expected four hexadecimal digits".to_string(),
+            })?;
+        let computed = crc16(&code.as_bytes()[..crc_field.value_offset]);
+        if found != computed {
+            return Err(BrCodeError::CrcMismatch { found, computed });
+        }
+
+        let find = |id: &str| {
+            rest.iter()
+                .find(|field| field.id == id)
+                .map(|field| field.value)
+        };
+        let require = |id: &'static str| find(id).ok_or(BrCodeError::MissingField { id });
+
+        let payload_format_indicator = require(PAYLOAD_FORMAT_INDICATOR)?;
+        if payload_format_indicator != "01" {
+            return Err(BrCodeError::InvalidField {
+                id: PAYLOAD_FORMAT_INDICATOR.to_string(),
+                reason: format!("unsupported version {payload_format_indicator}"),
+            });
+        }
+
+        let point_of_initiation = match find(POINT_OF_INITIATION_METHOD) {
+            None => None,
+            Some("11") => Some(PointOfInitiation::Static),
+            Some("12") => Some(PointOfInitiation::Dynamic),
+            Some(other) => {
+                return Err(BrCodeError::InvalidField {
+                    id: POINT_OF_INITIATION_METHOD.to_string(),
+                    reason: format!("unknown method {other}"),
+                })
+            }
+        };
+
+        let merchant_account = rest
+            .iter()
+            .filter(|field| matches!(field.id.parse::<u8>(), Ok(26..=51)))
+            .find_map(|field| parse_pix_account(field).transpose())
+            .ok_or(BrCodeError::NotPix)??;
+
+        let amount = find(TRANSACTION_AMOUNT)
+            .map(|amount| {
+                parse_amount(amount).ok_or_else(|| BrCodeError::InvalidField {
+                    id: TRANSACTION_AMOUNT.to_string(),
+                    reason: format!("invalid amount {amount}"),
+                })
+            })
+            .transpose()?;
+
+        let txid = match find(ADDITIONAL_DATA) {
+            Some(value) => parse_tlv(value)?
+                .into_iter()
+                .find(|field| field.id == ADDITIONAL_DATA_TXID)
+                .map(|field| field.value.to_string()),
+            None => None,
+        };
+
+        Ok(BrCode {
+            payload_format_indicator: payload_format_indicator.to_string(),
+            point_of_initiation,
+            merchant_account,
+            merchant_category_code: require(MERCHANT_CATEGORY_CODE)?.to_string(),
+            currency: require(TRANSACTION_CURRENCY)?.to_string(),
+            amount,
+            country_code: require(COUNTRY_CODE)?.to_string(),
+            merchant_name: require(MERCHANT_NAME)?.to_string(),
+            merchant_city: require(MERCHANT_CITY)?.to_string(),
+            postal_code: find(POSTAL_CODE).map(str::to_string),
+            txid,
+            crc: found,
+        })
+    }
+}
+
+impl FromStr for BrCode {
+    type Err = BrCodeError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        BrCode::parse(code)
+    }
+}
+
+/// Longest merchant name allowed in field `59`.
+pub const MAX_MERCHANT_NAME_LENGTH: usize = 25;
+/// Longest merchant city allowed in field `60`.
+pub const MAX_MERCHANT_CITY_LENGTH: usize = 15;
+/// Longest txid allowed in the additional data field.
+pub const MAX_TXID_LENGTH: usize = 25;
+/// Longest PIX key allowed in the merchant account information.
+pub const MAX_PIX_KEY_LENGTH: usize = 77;
+/// Longest transaction amount allowed in field `54`, e.g. `1234.50`.
+pub const MAX_AMOUNT_LENGTH: usize = 13;
+
+/// Builds a static PIX BR Code offline, without calling the API.
+///
+/// ```
+/// use abacatepay_rust_sdk::brcode::{BrCode, StaticBrCodeBuilder};
+///
+/// let payload = StaticBrCodeBuilder::new("contato@example.com", "Loja Exemplo", "SAO PAULO")
+///     .amount(1050)
+///     .txid("PEDIDO123")
+///     .build()
+///     .unwrap();
+///
+/// let code = BrCode::parse(&payload).unwrap();
+/// assert_eq!(code.amount, Some(1050));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticBrCodeBuilder {
+    key: String,
+    merchant_name: String,
+    merchant_city: String,
+    amount: Option<i64>,
+    txid: Option<String>,
+    description: Option<String>,
+}
+
+impl StaticBrCodeBuilder {
+    pub fn new(
+        key: impl Into<String>,
+        merchant_name: impl Into<String>,
+        merchant_city: impl Into<String>,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            merchant_name: merchant_name.into(),
+            merchant_city: merchant_city.into(),
+            amount: None,
+            txid: None,
+            description: None,
+        }
+    }
+
+    /// Fixes the amount, in cents. Without it the payer chooses the amount.
+    pub fn amount(mut self, amount: i64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Alphanumeric transaction id; defaults to `***` (none).
+    pub fn txid(mut self, txid: impl Into<String>) -> Self {
+        self.txid = Some(txid.into());
+        self
+    }
+
+    /// Free text shown to the payer.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Emits the payload, including the trailing CRC field.
+    pub fn build(self) -> Result<String, BrCodeError> {
+        let invalid = |id: &str, reason: String| BrCodeError::InvalidField {
+            id: id.to_string(),
+            reason,
+        };
+
+        check_length(MERCHANT_NAME, &self.merchant_name, MAX_MERCHANT_NAME_LENGTH)?;
+        check_length(MERCHANT_CITY, &self.merchant_city, MAX_MERCHANT_CITY_LENGTH)?;
+        check_length(PIX_KEY, &self.key, MAX_PIX_KEY_LENGTH)?;
+        let txid = self.txid.as_deref().unwrap_or("***");
+        check_length(ADDITIONAL_DATA_TXID, txid, MAX_TXID_LENGTH)?;
+        if txid != "***" && !txid.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid(
+                ADDITIONAL_DATA_TXID,
+                "must be alphanumeric".to_string(),
+            ));
+        }
+
+        let mut account = String::new();
+        push_field(&mut account, PIX_GUI_ID, PIX_GUI)?;
+        push_field(&mut account, PIX_KEY, &self.key)?;
+        if let Some(description) = &self.description {
+            push_field(&mut account, PIX_DESCRIPTION, description)?;
+        }
+
+        let mut additional_data = String::new();
+        push_field(&mut additional_data, ADDITIONAL_DATA_TXID, txid)?;
+
+        let mut payload = String::new();
+        push_field(&mut payload, PAYLOAD_FORMAT_INDICATOR, "01")?;
+        push_field(&mut payload, MERCHANT_ACCOUNT_INFORMATION, &account)?;
+        push_field(&mut payload, MERCHANT_CATEGORY_CODE, "0000")?;
+        push_field(&mut payload, TRANSACTION_CURRENCY, "986")?;
+        if let Some(amount) = self.amount {
+            if amount <= 0 {
+                return Err(invalid(
+                    TRANSACTION_AMOUNT,
+                    "must be greater than zero".to_string(),
+                ));
+            }
+            let amount = format!("{}.{:02}", amount / 100, amount % 100);
+            check_length(TRANSACTION_AMOUNT, &amount, MAX_AMOUNT_LENGTH)?;
+            push_field(&mut payload, TRANSACTION_AMOUNT, &amount)?;
+        }
+        push_field(&mut payload, COUNTRY_CODE, "BR")?;
+        push_field(&mut payload, MERCHANT_NAME, &self.merchant_name)?;
+        push_field(&mut payload, MERCHANT_CITY, &self.merchant_city)?;
+        push_field(&mut payload, ADDITIONAL_DATA, &additional_data)?;
+
+        payload.push_str(CRC);
+        payload.push_str("04");
+        let crc = crc16(payload.as_bytes());
+        payload.push_str(&format!("{crc:04X}"));
+        Ok(payload)
+    }
+}
+
+fn check_length(id: &str, value: &str, max: usize) -> Result<(), BrCodeError> {
+    let length = value.chars().count();
+    if length == 0 || length > max {
+        return Err(BrCodeError::InvalidField {
+            id: id.to_string(),
+            reason: format!("must be between 1 and {max} characters"),
+        });
+    }
+    Ok(())
+}
+
+/// Appends one TLV field, failing when the value does not fit the two digit length.
+fn push_field(out: &mut String, id: &str, value: &str) -> Result<(), BrCodeError> {
+    let length = value.chars().count();
+    if length > 99 {
+        return Err(BrCodeError::InvalidField {
+            id: id.to_string(),
+            reason: "longer than 99 characters".to_string(),
+        });
+    }
+    out.push_str(id);
+    out.push_str(&format!("{length:02}"));
+    out.push_str(value);
+    Ok(())
+}
+
+/// CRC16-CCITT (polynomial `0x1021`, initial value `0xFFFF`) as required by the BR Code spec.
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, &byte| {
+        (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+struct Field<'a> {
+    id: &'a str,
+    value: &'a str,
+    /// Byte offset of the value within the input the field was parsed from.
+    value_offset: usize,
+}
+
+/// Splits `input` into TLV fields. Lengths count characters, so `position` in errors is a
+/// character offset within `input`.
+fn parse_tlv(input: &str) -> Result<Vec<Field<'_>>, BrCodeError> {
+    let mut fields = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    let mut position = 0;
+
+    while chars.peek().is_some() {
+        let header: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+        if header.len() < 4 {
+            return Err(BrCodeError::Truncated { position });
+        }
+        if !header.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(BrCodeError::InvalidHeader { position });
+        }
+        let length: usize = header[2..].parse().expect("header digits were checked");
+
+        let start = chars.peek().map_or(input.len(), |&(offset, _)| offset);
+        let end = match chars.by_ref().take(length).last() {
+            Some((offset, c)) if length > 0 => offset + c.len_utf8(),
+            _ if length == 0 => start,
+            _ => return Err(BrCodeError::Truncated { position }),
+        };
+        let value = &input[start..end];
+        if value.chars().count() != length {
+            return Err(BrCodeError::Truncated { position });
+        }
+
+        let header_start = start - 4;
+        fields.push(Field {
+            id: &input[header_start..header_start + 2],
+            value,
+            value_offset: start,
+        });
+        position += 4 + length;
+    }
+
+    Ok(fields)
+}
+
+fn parse_pix_account(field: &Field<'_>) -> Result<Option<PixMerchantAccount>, BrCodeError> {
+    let nested = parse_tlv(field.value)?;
+    let find = |id: &str| {
+        nested
+            .iter()
+            .find(|field| field.id == id)
+            .map(|field| field.value.to_string())
+    };
+
+    let is_pix = find(PIX_GUI_ID).is_some_and(|gui| gui.eq_ignore_ascii_case(PIX_GUI));
+    if !is_pix {
+        return Ok(None);
+    }
+
+    let account = PixMerchantAccount {
+        key: find(PIX_KEY),
+        description: find(PIX_DESCRIPTION),
+        url: find(PIX_URL),
+    };
+    if account.key.is_none() && account.url.is_none() {
+        return Err(BrCodeError::InvalidField {
+            id: MERCHANT_ACCOUNT_INFORMATION.to_string(),
+            reason: "neither a PIX key nor a payload URL is present".to_string(),
+        });
+    }
+    Ok(Some(account))
+}
+
+/// Parses a decimal amount such as `10.5` or `10.50` into cents.
+fn parse_amount(amount: &str) -> Option<i64> {
+    let (units, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if units.is_empty()
+        || fraction.len() > 2
+        || !units
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let cents: i64 = format!("{fraction:0<2}").parse().ok()?;
+    units
+        .parse::<i64>()
+        .ok()?
+        .checked_mul(100)?
+        .checked_add(cents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Static example from the Banco Central "Manual de Padrões para Iniciação do Pix".
+    const BCB_STATIC: &str = "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-4266554400005204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D";
+
+    fn with_crc(payload: &str) -> String {
+        let payload = format!("{payload}6304");
+        format!("{payload}{:04X}", crc16(payload.as_bytes()))
+    }
+
+    #[test]
+    fn crc16_of_bcb_example() {
+        let payload = &BCB_STATIC[..BCB_STATIC.len() - 4];
+
+        assert_eq!(crc16(payload.as_bytes()), 0x1D3D);
+    }
+
+    #[test]
+    fn parses_static_code() {
+        let code = BrCode::parse(BCB_STATIC).unwrap();
+
+        assert_eq!(code.payload_format_indicator, "01");
+        assert_eq!(code.point_of_initiation, None);
+        assert_eq!(
+            code.merchant_account.key.as_deref(),
+            Some("123e4567-e12b-12d1-a456-426655440000")
+        );
+        assert_eq!(code.merchant_category_code, "0000");
+        assert_eq!(code.currency, "986");
+        assert_eq!(code.amount, None);
+        assert_eq!(code.country_code, "BR");
+        assert_eq!(code.merchant_name, "Fulano de Tal");
+        assert_eq!(code.merchant_city, "BRASILIA");
+        assert_eq!(code.txid.as_deref(), Some("***"));
+        assert_eq!(code.crc, 0x1D3D);
+    }
+
+    #[test]
+    fn parses_dynamic_code_with_amount() {
+        let code = with_crc(
+            "000201010212\
+             26530014br.gov.bcb.pix2531pix.example.com/qr/v2/cobv/9d36\
+             52040000530398654041.505802BR5910Loja Teste6009SAO PAULO\
+             62140510TXID123456",
+        );
+
+        let code: BrCode = code.parse().unwrap();
+
+        assert_eq!(code.point_of_initiation, Some(PointOfInitiation::Dynamic));
+        assert_eq!(
+            code.merchant_account.url.as_deref(),
+            Some("pix.example.com/qr/v2/cobv/9d36")
+        );
+        assert_eq!(code.amount, Some(150));
+        assert_eq!(code.merchant_name, "Loja Teste");
+        assert_eq!(code.merchant_city, "SAO PAULO");
+        assert_eq!(code.txid.as_deref(), Some("TXID123456"));
+    }
+
+    #[test]
+    fn rejects_bad_crc() {
+        let code = BCB_STATIC.replace("63041D3D", "63041D3E");
+
+        assert_eq!(
+            BrCode::parse(&code),
+            Err(BrCodeError::CrcMismatch {
+                found: 0x1D3E,
+                computed: 0x1D3D
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_code() {
+        assert_eq!(
+            BrCode::parse(&BCB_STATIC[..20]),
+            Err(BrCodeError::Truncated { position: 6 })
+        );
+    }
+
+    #[test]
+    fn rejects_non_pix_code() {
+        let code = with_crc(
+            "00020126250014br.gov.bcb.xyz0103abc52040000530398658\
+             02BR5913Fulano de Tal6008BRASILIA",
+        );
+
+        assert_eq!(BrCode::parse(&code), Err(BrCodeError::NotPix));
+    }
+
+    #[test]
+    fn rejects_missing_merchant_name() {
+        let code = with_crc(
+            "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000\
+             5204000053039865802BR6008BRASILIA",
+        );
+
+        assert_eq!(
+            BrCode::parse(&code),
+            Err(BrCodeError::MissingField { id: MERCHANT_NAME })
+        );
+    }
+
+    #[test]
+    fn amount_parsing() {
+        assert_eq!(parse_amount("10"), Some(1000));
+        assert_eq!(parse_amount("10.5"), Some(1050));
+        assert_eq!(parse_amount("0.01"), Some(1));
+        assert_eq!(parse_amount("1.234"), None);
+        assert_eq!(parse_amount("-1.00"), None);
+        assert_eq!(parse_amount(".50"), None);
+    }
+
+    #[test]
+    fn generates_bcb_example() {
+        let payload = StaticBrCodeBuilder::new(
+            "123e4567-e12b-12d1-a456-426655440000",
+            "Fulano de Tal",
+            "BRASILIA",
+        )
+        .build()
+        .unwrap();
+
+        assert_eq!(payload, BCB_STATIC);
+    }
+
+    #[test]
+    fn generated_code_round_trips() {
+        let payload = StaticBrCodeBuilder::new("+5511999999999", "Loja Teste", "SAO PAULO")
+            .amount(1999)
+            .txid("PEDIDO42")
+            .description("Pedido 42")
+            .build()
+            .unwrap();
+
+        let code = BrCode::parse(&payload).unwrap();
+
+        assert!(payload.contains("540519.99"));
+        assert_eq!(code.merchant_account.key.as_deref(), Some("+5511999999999"));
+        assert_eq!(
+            code.merchant_account.description.as_deref(),
+            Some("Pedido 42")
+        );
+        assert_eq!(code.amount, Some(1999));
+        assert_eq!(code.txid.as_deref(), Some("PEDIDO42"));
+    }
+
+    #[test]
+    fn generator_rejects_invalid_fields() {
+        let builder = StaticBrCodeBuilder::new("key", "Loja", "SAO PAULO");
+
+        assert!(matches!(
+            builder.clone().txid("PEDIDO-42").build(),
+            Err(BrCodeError::InvalidField { id, .. }) if id == ADDITIONAL_DATA_TXID
+        ));
+        assert!(matches!(
+            builder.clone().amount(0).build(),
+            Err(BrCodeError::InvalidField { id, .. }) if id == TRANSACTION_AMOUNT
+        ));
+        assert!(matches!(
+            StaticBrCodeBuilder::new("key", "Loja", "SAO JOSE DOS CAMPOS").build(),
+            Err(BrCodeError::InvalidField { id, .. }) if id == MERCHANT_CITY
+        ));
+    }
+
+    #[test]
+    fn generator_enforces_amount_and_key_lengths() {
+        let builder = StaticBrCodeBuilder::new("key", "Loja", "SAO PAULO");
+        let longest_key = "k".repeat(MAX_PIX_KEY_LENGTH);
+
+        assert!(builder.clone().amount(999_999_999_999).build().is_ok());
+        assert!(matches!(
+            builder.clone().amount(1_000_000_000_000).build(),
+            Err(BrCodeError::InvalidField { id, .. }) if id == TRANSACTION_AMOUNT
+        ));
+        assert!(matches!(
+            builder.amount(i64::MAX).build(),
+            Err(BrCodeError::InvalidField { id, .. }) if id == TRANSACTION_AMOUNT
+        ));
+        assert!(
+            StaticBrCodeBuilder::new(longest_key.clone(), "Loja", "SAO PAULO")
+                .build()
+                .is_ok()
+        );
+        assert!(matches!(
+            StaticBrCodeBuilder::new(format!("{longest_key}k"), "Loja", "SAO PAULO")
+                .build(),
+            Err(BrCodeError::InvalidField { id, .. }) if id == PIX_KEY
+        ));
+    }
+}
@@ -1,15 +1,28 @@
+use crate::api_key::{ApiKey, KeyMode};
 use crate::billing::{
-    Billing, BillingMethods, CreateBillingData, CreateBillingProduct, CreateBillingResponse,
-    CustomerMetadata, ListBillingResponse,
+    Billing, BillingFilter, BillingMethods, BillingStatus, CreateBillingData, CreateBillingProduct,
+    CreateBillingResponse, CustomerMetadata, ListBillingResponse,
 };
+use crate::ids::{BillingId, CustomerId, PixChargeId};
+use crate::meta::ResponseMeta;
 use crate::pix_charge::{
     CheckPixStatusData, CheckPixStatusResponse, CreatePixChargeData, PixChargeData,
-    PixChargeResponse,
+    PixChargeResponse, PixStatus,
 };
-use crate::{billing::BillingKind, error::AbacatePayError};
-use reqwest::{Client, StatusCode};
-use serde::Deserialize;
-use tracing::{debug, error, instrument};
+use crate::redact::BodyLogging;
+use crate::refund::{CreateRefundData, Refund, RefundResponse};
+use crate::{
+    billing::BillingKind,
+    error::{AbacatePayError, ErrorCode},
+};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, TryStreamExt};
+use reqwest::header::AUTHORIZATION;
+use reqwest::{Client, Method, RequestBuilder, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::marker::PhantomData;
+use tracing::{debug, error, field, info_span, instrument, Instrument, Span};
 
 #[derive(Debug, Deserialize)]
 struct ErrorResponse {
@@ -20,11 +33,51 @@ struct ErrorResponse {
 
 pub struct AbacatePay {
     client: Client,
-    api_key: String,
+    api_key: ApiKey,
     base_url: String,
+    body_logging: BodyLogging,
+}
+
+impl fmt::Debug for AbacatePay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AbacatePay")
+            .field("api_key", &self.api_key)
+            .field("base_url", &self.base_url)
+            .field("body_logging", &self.body_logging)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Typestate marker for a required billing field that has not been set yet.
+pub struct Missing;
+
+/// Typestate marker for a required billing field that has been set.
+pub struct Set;
+
+/// Builder for a new billing.
+///
+/// The type parameters track whether products, methods, return URL and completion URL
+/// have been provided, and `build()` is only available once all of them are:
+///
+/// ```compile_fail
+/// # use abacatepay_rust_sdk::AbacatePay;
+/// # async fn create(client: &AbacatePay) {
+/// // No product, method or completion URL: `build` does not exist yet.
+/// client
+///     .create_billing()
+///     .return_url("http://localhost:3000/".to_string())
+///     .build()
+///     .await;
+/// # }
+/// ```
+pub struct BillingBuilder<'a, P = Missing, M = Missing, R = Missing, C = Missing> {
+    client: &'a AbacatePay,
+    data: CreateBillingData,
+    state: PhantomData<(P, M, R, C)>,
 }
 
-pub struct BillingBuilder<'a> {
+/// Builder for a new billing without compile-time checks on required fields.
+pub struct UncheckedBillingBuilder<'a> {
     client: &'a AbacatePay,
     data: CreateBillingData,
 }
@@ -36,29 +89,136 @@ pub struct PixChargeBuilder<'a> {
 
 pub struct SimulatePixPaymentBuilder<'a> {
     client: &'a AbacatePay,
-    id: String,
+    id: PixChargeId,
 }
 
 pub struct CheckPixStatusBuilder<'a> {
     client: &'a AbacatePay,
-    id: String,
+    id: PixChargeId,
+    resend_count: u32,
+}
+
+pub struct ListBillingsBuilder<'a> {
+    client: &'a AbacatePay,
+    filter: BillingFilter,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+pub struct CancelBillingBuilder<'a> {
+    client: &'a AbacatePay,
+    id: BillingId,
+}
+
+pub struct CancelPixChargeBuilder<'a> {
+    client: &'a AbacatePay,
+    id: PixChargeId,
+}
+
+/// Builder for a refund. Clone it before `build()` to retry the same refund, with the same
+/// idempotency key, after a failure.
+#[derive(Clone)]
+pub struct RefundBuilder<'a, Id> {
+    client: &'a AbacatePay,
+    path: &'static str,
+    data: CreateRefundData<Id>,
+    idempotency_key: String,
+}
+
+pub struct BatchCheckPixStatusBuilder<'a> {
+    client: &'a AbacatePay,
+    ids: Vec<PixChargeId>,
+    concurrency: usize,
 }
 
 impl AbacatePay {
-    pub fn new(api_key: String) -> Self {
+    pub fn new(api_key: impl Into<ApiKey>) -> Self {
         Self {
             client: Client::new(),
-            api_key,
+            api_key: api_key.into(),
             base_url: "https://api.abacatepay.com/v1".to_string(),
+            body_logging: BodyLogging::default(),
         }
     }
-    pub fn create_simulate_pix_payment(&self, id: String) -> SimulatePixPaymentBuilder {
+
+    /// Creates a client with the key in the `ABACATEPAY_API_KEY` environment variable.
+    pub fn from_env() -> Result<Self, AbacatePayError> {
+        Ok(Self::new(ApiKey::from_env()?))
+    }
+
+    /// Creates a client with the key stored in the file at `path`.
+    pub fn from_key_file(path: impl AsRef<std::path::Path>) -> Result<Self, AbacatePayError> {
+        Ok(Self::new(ApiKey::from_file(path)?))
+    }
+
+    /// Sends requests to `base_url` instead of the production API, e.g. a proxy or mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// The environment of the API key, as told by its prefix.
+    pub fn key_mode(&self) -> KeyMode {
+        self.api_key.mode()
+    }
+
+    /// Whether the API key is a dev mode (sandbox) key.
+    pub fn is_dev_mode(&self) -> bool {
+        self.key_mode() == KeyMode::Dev
+    }
+
+    /// Fails with `AbacatePayError::DevKeyInRelease` when a release build is given a dev key.
+    ///
+    /// Call it at startup so a misconfigured deployment never takes fake payments.
+    pub fn deny_dev_key_in_release(self) -> Result<Self, AbacatePayError> {
+        self.deny_dev_key(!cfg!(debug_assertions))
+    }
+
+    fn deny_dev_key(self, release: bool) -> Result<Self, AbacatePayError> {
+        if release && self.is_dev_mode() {
+            return Err(AbacatePayError::DevKeyInRelease);
+        }
+        Ok(self)
+    }
+
+    /// Sets how request and response bodies appear in debug logs; redacted by default.
+    pub fn with_body_logging(mut self, body_logging: BodyLogging) -> Self {
+        self.body_logging = body_logging;
+        self
+    }
+    pub fn create_simulate_pix_payment(&self, id: PixChargeId) -> SimulatePixPaymentBuilder<'_> {
         SimulatePixPaymentBuilder { client: self, id }
     }
-    pub fn check_pix_status(&self, id: String) -> CheckPixStatusBuilder {
-        CheckPixStatusBuilder { client: self, id }
+    pub fn check_pix_status(&self, id: PixChargeId) -> CheckPixStatusBuilder<'_> {
+        CheckPixStatusBuilder {
+            client: self,
+            id,
+            resend_count: 0,
+        }
+    }
+    pub fn batch_check_pix_status(
+        &self,
+        ids: impl IntoIterator<Item = PixChargeId>,
+    ) -> BatchCheckPixStatusBuilder<'_> {
+        BatchCheckPixStatusBuilder {
+            client: self,
+            ids: ids.into_iter().collect(),
+            concurrency: 10,
+        }
+    }
+    pub fn cancel_billing(&self, id: BillingId) -> CancelBillingBuilder<'_> {
+        CancelBillingBuilder { client: self, id }
+    }
+    pub fn cancel_pix_charge(&self, id: PixChargeId) -> CancelPixChargeBuilder<'_> {
+        CancelPixChargeBuilder { client: self, id }
+    }
+    pub fn refund_billing(&self, id: BillingId) -> RefundBuilder<'_, BillingId> {
+        RefundBuilder::new(self, "billing/refund", id)
     }
-    pub fn create_pix_charge(&self) -> PixChargeBuilder {
+    pub fn refund_pix_charge(&self, id: PixChargeId) -> RefundBuilder<'_, PixChargeId> {
+        RefundBuilder::new(self, "pixQrCode/refund", id)
+    }
+    pub fn create_pix_charge(&self) -> PixChargeBuilder<'_> {
         PixChargeBuilder {
             client: self,
             data: CreatePixChargeData {
@@ -70,7 +230,7 @@ impl AbacatePay {
         }
     }
 
-    pub fn create_billing(&self) -> BillingBuilder {
+    pub fn create_billing(&self) -> BillingBuilder<'_> {
         BillingBuilder {
             client: self,
             data: CreateBillingData {
@@ -81,27 +241,45 @@ impl AbacatePay {
                 completion_url: String::new(),
                 customer_id: None,
             },
+            state: PhantomData,
         }
     }
 
-    #[instrument(skip(self))]
-    pub async fn list_billings(&self) -> Result<Vec<Billing>, AbacatePayError> {
-        let url = format!("{}/billing/list", self.base_url);
+    #[deprecated(note = "use `create_billing`, which checks required fields at compile time")]
+    pub fn create_billing_unchecked(&self) -> UncheckedBillingBuilder<'_> {
+        UncheckedBillingBuilder {
+            client: self,
+            data: self.create_billing().data,
+        }
+    }
 
-        debug!(url = url.as_str(), "Sending list billings request");
+    /// Lists billings matching filters, one page at a time.
+    ///
+    /// `billing/list` has no server-side filtering or paging, so the full list is fetched
+    /// once and the filters, offset and limit are applied client-side.
+    pub fn query_billings(&self) -> ListBillingsBuilder<'_> {
+        ListBillingsBuilder {
+            client: self,
+            filter: BillingFilter::default(),
+            offset: 0,
+            limit: None,
+        }
+    }
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header(
-                "User-Agent",
-                format!("Rust SDK {}", env!("CARGO_PKG_VERSION")),
-            )
-            .send()
-            .await?;
+    pub async fn list_billings(&self) -> Result<Vec<Billing>, AbacatePayError> {
+        self.list_billings_with_meta()
+            .await
+            .map(|(billings, _)| billings)
+    }
 
-        let result: ListBillingResponse = self.handle_response(response).await?;
+    /// Like [`list_billings`](Self::list_billings), also returning the response metadata.
+    #[instrument(skip(self))]
+    pub async fn list_billings_with_meta(
+        &self,
+    ) -> Result<(Vec<Billing>, ResponseMeta), AbacatePayError> {
+        let (result, meta): (ListBillingResponse, ResponseMeta) = self
+            .send(Method::GET, "billing/list", |request| request)
+            .await?;
 
         match result {
             ListBillingResponse::Success { billings, .. } => {
@@ -109,7 +287,7 @@ impl AbacatePay {
                     billing_count = billings.len(),
                     "Successfully retrieved billings"
                 );
-                Ok(billings)
+                Ok((billings, meta))
             }
             ListBillingResponse::Error {
                 error,
@@ -122,90 +300,263 @@ impl AbacatePay {
                 );
                 Err(AbacatePayError::ApiError {
                     status: StatusCode::OK,
-                    code: code,
-                    error: error,
-                    message: message,
+                    code: code.into(),
+                    error,
+                    message,
+                    meta: Box::new(meta),
+                })
+            }
+        }
+    }
+
+    /// Fetches a single billing by id.
+    ///
+    /// The API has no endpoint for a single billing, so this lists all billings and picks
+    /// the one whose `id`, `pId` or `publicId` matches, returning
+    /// `AbacatePayError::NotFound` when none does. Its cost grows with the billing history.
+    #[instrument(skip(self))]
+    pub async fn get_billing(&self, id: BillingId) -> Result<Billing, AbacatePayError> {
+        self.list_billings()
+            .await?
+            .into_iter()
+            .find(|billing| billing.id == id || billing._id == id || billing.public_id == id)
+            .ok_or_else(|| AbacatePayError::NotFound {
+                resource: "billing",
+                id: id.into_inner(),
+            })
+    }
+
+    #[instrument(skip(self, data))]
+    async fn send_create_billing(
+        &self,
+        data: &CreateBillingData,
+    ) -> Result<(Billing, ResponseMeta), AbacatePayError> {
+        data.validate()?;
+
+        debug!(
+            request_data = self.body_logging.request(data),
+            "Sending create billing request"
+        );
+
+        let (result, meta): (CreateBillingResponse, ResponseMeta) = self
+            .send(Method::POST, "billing/create", |request| request.json(data))
+            .await?;
+
+        match result {
+            CreateBillingResponse::Success { billing, .. } => {
+                debug!(billing_id = ?billing._id, "Successfully created billing");
+                Ok((billing, meta))
+            }
+            CreateBillingResponse::Error {
+                error,
+                code,
+                message,
+            } => {
+                error!(
+                    error = error.as_str(),
+                    "API returned error in response body"
+                );
+                Err(AbacatePayError::ApiError {
+                    status: StatusCode::OK,
+                    code: code.into(),
+                    error,
+                    message,
+                    meta: Box::new(meta),
                 })
             }
         }
     }
 
-    async fn handle_response<T>(&self, response: reqwest::Response) -> Result<T, AbacatePayError>
+    /// Sends a request to `path` with the client's credentials and decodes the response,
+    /// returned with its metadata.
+    ///
+    /// `configure` adds the query, body and extra headers of the call.
+    async fn send<T>(
+        &self,
+        method: Method,
+        path: &'static str,
+        configure: impl FnOnce(RequestBuilder) -> RequestBuilder,
+    ) -> Result<(T, ResponseMeta), AbacatePayError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.send_attempt(method, path, 0, configure).await
+    }
+
+    /// Like [`send`](Self::send), for the `resend_count`-th retry of the same call.
+    ///
+    /// The request runs in a span following the OpenTelemetry HTTP client conventions.
+    async fn send_attempt<T>(
+        &self,
+        method: Method,
+        path: &'static str,
+        resend_count: u32,
+        configure: impl FnOnce(RequestBuilder) -> RequestBuilder,
+    ) -> Result<(T, ResponseMeta), AbacatePayError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        // Built per request rather than cached, so no copy of the key outlives the request.
+        let authorization = self.api_key.authorization_header().ok_or_else(|| {
+            AbacatePayError::Config("the API key is not a valid HTTP header value".to_string())
+        })?;
+        let url = format!("{}/{}", self.base_url, path);
+        let parsed = Url::parse(&url).ok();
+        let span = info_span!(
+            "abacatepay.request",
+            otel.name = %format_args!("{method} {path}"),
+            otel.kind = "client",
+            otel.status_code = field::Empty,
+            http.request.method = %method,
+            http.request.resend_count = field::Empty,
+            http.response.status_code = field::Empty,
+            url.full = url.as_str(),
+            url.template = path,
+            server.address = parsed.as_ref().and_then(Url::host_str),
+            server.port = parsed
+                .as_ref()
+                .and_then(Url::port_or_known_default)
+                .map(i64::from),
+            "error.type" = field::Empty,
+        );
+        if resend_count > 0 {
+            span.record("http.request.resend_count", i64::from(resend_count));
+        }
+
+        async move {
+            debug!("Sending request");
+
+            let request = self
+                .client
+                .request(method.clone(), &url)
+                .header(AUTHORIZATION, authorization)
+                .header(
+                    "User-Agent",
+                    format!("Rust SDK {}", env!("CARGO_PKG_VERSION")),
+                );
+            #[cfg(feature = "opentelemetry")]
+            let request = crate::telemetry::inject_trace_context(request);
+
+            #[cfg(feature = "metrics")]
+            let started = std::time::Instant::now();
+            let response = configure(request).send().await;
+            let status = response.as_ref().ok().map(reqwest::Response::status);
+            if let Some(status) = status {
+                Span::current().record("http.response.status_code", i64::from(status.as_u16()));
+            }
+
+            let result = match response {
+                Ok(response) => self.handle_response(response).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = &result {
+                Span::current()
+                    .record("error.type", e.kind())
+                    .record("otel.status_code", "ERROR");
+            }
+
+            #[cfg(feature = "metrics")]
+            crate::metrics::record_request(&method, path, status, &result, started.elapsed());
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn handle_response<T>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<(T, ResponseMeta), AbacatePayError>
     where
         T: serde::de::DeserializeOwned,
     {
         let status = response.status();
+        let meta = ResponseMeta::from_headers(status, response.headers());
         let response_text = response.text().await?;
 
         debug!(
             status = status.as_u16(),
-            response = response_text.as_str(),
+            response = self.body_logging.response(&response_text),
             "Received response"
         );
 
-        if !status.is_success() {
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&response_text) {
-                error!(
-                    status = status.as_u16(),
-                    error = error_response.error.as_str(),
-                    "API error response"
-                );
-                return Err(AbacatePayError::ApiError {
-                    status,
-                    code: error_response.code,
-                    error: error_response.error,
-                    message: error_response.message,
-                });
-            }
+        // The API also reports some errors in the body of a 2xx response; catching them here
+        // lets spans and metrics count them as errors.
+        if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&response_text) {
+            error!(
+                status = status.as_u16(),
+                error = error_response.error.as_str(),
+                "API error response"
+            );
+            return Err(AbacatePayError::ApiError {
+                status,
+                code: error_response.code.into(),
+                error: error_response.error,
+                message: error_response.message,
+                meta: Box::new(meta),
+            });
+        }
 
+        if !status.is_success() {
             return Err(AbacatePayError::UnexpectedResponse {
                 status,
-                response: response_text,
+                response: self.body_logging.error_body(&response_text),
+                meta: Box::new(meta),
             });
         }
 
         match serde_json::from_str::<T>(&response_text) {
-            Ok(parsed) => Ok(parsed),
+            Ok(parsed) => Ok((parsed, meta)),
             Err(e) => {
+                let message = self.body_logging.parse_error(&e);
                 error!(
-                    error = ?e,
-                    response = response_text.as_str(),
+                    error = message.as_str(),
+                    response = self.body_logging.response(&response_text),
                     "Failed to parse API response"
                 );
                 Err(AbacatePayError::ParseError {
-                    message: e.to_string(),
-                    response: response_text,
+                    message,
+                    response: self.body_logging.error_body(&response_text),
+                    meta: Box::new(meta),
                 })
             }
         }
     }
 }
 impl CheckPixStatusBuilder<'_> {
-    pub fn id(mut self, id: String) -> Self {
+    pub fn id(mut self, id: PixChargeId) -> Self {
         self.id = id;
         self
     }
 
-    #[instrument(skip(self))]
+    /// Marks this call as the `count`-th retry of the same check, for tracing.
+    pub(crate) fn resend_count(mut self, count: u32) -> Self {
+        self.resend_count = count;
+        self
+    }
+
     pub async fn build(self) -> Result<CheckPixStatusData, AbacatePayError> {
-        let url = format!("{}/pixQrCode/check", self.client.base_url);
-        let response = self
-            .client
+        self.build_with_meta().await.map(|(data, _)| data)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
+    #[instrument(skip(self))]
+    pub async fn build_with_meta(
+        self,
+    ) -> Result<(CheckPixStatusData, ResponseMeta), AbacatePayError> {
+        let (result, meta): (CheckPixStatusResponse, ResponseMeta) = self
             .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.client.api_key))
-            .header(
-                "User-Agent",
-                format!("Rust SDK {}", env!("CARGO_PKG_VERSION")),
+            .send_attempt(
+                Method::POST,
+                "pixQrCode/check",
+                self.resend_count,
+                |request| request.query(&[("id", self.id.as_str())]),
             )
-            .query(&["id", &self.id])
-            .send()
             .await?;
-        let result: CheckPixStatusResponse = self.client.handle_response(response).await?;
         match result {
             CheckPixStatusResponse::Success { data, .. } => {
                 debug!(pix_charge_id = ?data.status, "Successfully get the status of the PIX payment");
-                Ok(data)
+                Ok((data, meta))
             }
             CheckPixStatusResponse::Error {
                 error,
@@ -218,41 +569,375 @@ impl CheckPixStatusBuilder<'_> {
                 );
                 Err(AbacatePayError::ApiError {
                     status: StatusCode::OK,
-                    code,
+                    code: code.into(),
                     error,
                     message,
+                    meta: Box::new(meta),
                 })
             }
         }
     }
 }
 
-impl SimulatePixPaymentBuilder<'_> {
-    pub fn id(mut self, id: String) -> Self {
+impl<'a> ListBillingsBuilder<'a> {
+    /// Keeps billings in this status; may be called several times to accept any of them.
+    pub fn status(mut self, status: BillingStatus) -> Self {
+        self.filter.statuses.push(status);
+        self
+    }
+
+    /// Keeps billings created at or after `from`.
+    pub fn created_from(mut self, from: DateTime<Utc>) -> Self {
+        self.filter.created_from = Some(from);
+        self
+    }
+
+    /// Keeps billings created strictly before `until`.
+    pub fn created_until(mut self, until: DateTime<Utc>) -> Self {
+        self.filter.created_until = Some(until);
+        self
+    }
+
+    pub fn customer_id(mut self, id: CustomerId) -> Self {
+        self.filter.customer_id = Some(id);
+        self
+    }
+
+    pub fn frequency(mut self, frequency: BillingKind) -> Self {
+        self.filter.frequency = Some(frequency);
+        self
+    }
+
+    /// Skips the first `offset` matching billings.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns at most `limit` matching billings.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub async fn build(self) -> Result<Vec<Billing>, AbacatePayError> {
+        self.into_stream().try_collect().await
+    }
+
+    /// Streams matching billings. Nothing is fetched until the stream is first polled.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Billing, AbacatePayError>> + 'a {
+        let Self {
+            client,
+            filter,
+            offset,
+            limit,
+        } = self;
+
+        futures::stream::once(async move {
+            let page: Vec<_> = client
+                .list_billings()
+                .await?
+                .into_iter()
+                .filter(|billing| filter.matches(billing))
+                .skip(offset)
+                .take(limit.unwrap_or(usize::MAX))
+                .map(Ok)
+                .collect();
+            Ok::<_, AbacatePayError>(futures::stream::iter(page))
+        })
+        .try_flatten()
+    }
+}
+
+impl BatchCheckPixStatusBuilder<'_> {
+    /// Maximum number of status checks in flight at once (at least 1).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Checks every charge, returning one result per id in the order they were given.
+    /// A failed check does not abort the rest of the batch.
+    #[instrument(skip(self), fields(count = self.ids.len(), concurrency = self.concurrency))]
+    pub async fn build(self) -> Vec<(PixChargeId, Result<CheckPixStatusData, AbacatePayError>)> {
+        let client = self.client;
+        let results: Vec<_> = futures::stream::iter(self.ids)
+            .map(|id| async move {
+                let result = client.check_pix_status(id.clone()).build().await;
+                (id, result)
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await;
+
+        debug!(
+            failed = results.iter().filter(|(_, result)| result.is_err()).count(),
+            "Finished batch PIX status check"
+        );
+        results
+    }
+}
+
+/// Whether the API refused an operation because of the resource's current status, which
+/// is the only case worth looking the resource up to report `AlreadyPaid`.
+fn is_status_refusal(error: &AbacatePayError) -> bool {
+    match error {
+        AbacatePayError::ApiError { status, code, .. } => {
+            *code == ErrorCode::InvalidStatus || *status == StatusCode::CONFLICT
+        }
+        _ => false,
+    }
+}
+
+impl CancelBillingBuilder<'_> {
+    pub fn id(mut self, id: BillingId) -> Self {
         self.id = id;
         self
     }
 
+    /// Cancels the billing and returns it with its updated status.
+    ///
+    /// When the API refuses because of the billing's status and it turns out to be paid, the
+    /// error is reported as `AbacatePayError::AlreadyPaid`.
+    pub async fn build(self) -> Result<Billing, AbacatePayError> {
+        self.build_with_meta().await.map(|(billing, _)| billing)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
     #[instrument(skip(self))]
+    pub async fn build_with_meta(self) -> Result<(Billing, ResponseMeta), AbacatePayError> {
+        let result = self
+            .client
+            .send(Method::POST, "billing/cancel", |request| {
+                request.query(&[("id", self.id.as_str())])
+            })
+            .await;
+        let result = match result {
+            Ok((CreateBillingResponse::Success { billing, .. }, meta)) => {
+                debug!(billing_id = ?billing.id, status = ?billing.status, "Cancelled billing");
+                return Ok((billing, meta));
+            }
+            Ok((
+                CreateBillingResponse::Error {
+                    error,
+                    code,
+                    message,
+                },
+                meta,
+            )) => {
+                error!(
+                    error = error.as_str(),
+                    "API returned error in response body"
+                );
+                AbacatePayError::ApiError {
+                    status: StatusCode::OK,
+                    code: code.into(),
+                    error,
+                    message,
+                    meta: Box::new(meta),
+                }
+            }
+            Err(e) => e,
+        };
+
+        if is_status_refusal(&result) {
+            if let Ok(billing) = self.client.get_billing(self.id.clone()).await {
+                if billing.status == BillingStatus::PAID {
+                    return Err(AbacatePayError::AlreadyPaid {
+                        resource: "billing",
+                        id: self.id.into_inner(),
+                    });
+                }
+            }
+        }
+        Err(result)
+    }
+}
+
+impl CancelPixChargeBuilder<'_> {
+    pub fn id(mut self, id: PixChargeId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Cancels the PIX QR code charge and returns it with its updated status.
+    ///
+    /// When the API refuses because of the charge's status and it turns out to be paid, the
+    /// error is reported as `AbacatePayError::AlreadyPaid`.
     pub async fn build(self) -> Result<PixChargeData, AbacatePayError> {
-        let url = format!("{}/pixQrCode/simulate-payment", self.client.base_url);
-        let response = self
+        self.build_with_meta().await.map(|(data, _)| data)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
+    #[instrument(skip(self))]
+    pub async fn build_with_meta(self) -> Result<(PixChargeData, ResponseMeta), AbacatePayError> {
+        let result = self
+            .client
+            .send(Method::POST, "pixQrCode/cancel", |request| {
+                request.query(&[("id", self.id.as_str())])
+            })
+            .await;
+        let result = match result {
+            Ok((PixChargeResponse::Success { data, .. }, meta)) => {
+                debug!(pix_charge_id = ?data.id, status = ?data.status, "Cancelled PIX charge");
+                return Ok((data, meta));
+            }
+            Ok((
+                PixChargeResponse::Error {
+                    error,
+                    code,
+                    message,
+                },
+                meta,
+            )) => {
+                error!(
+                    error = error.as_str(),
+                    "API returned error in response body"
+                );
+                AbacatePayError::ApiError {
+                    status: StatusCode::OK,
+                    code: code.into(),
+                    error,
+                    message,
+                    meta: Box::new(meta),
+                }
+            }
+            Err(e) => e,
+        };
+
+        if is_status_refusal(&result) {
+            if let Ok(data) = self.client.check_pix_status(self.id.clone()).build().await {
+                if data.status == PixStatus::PAID {
+                    return Err(AbacatePayError::AlreadyPaid {
+                        resource: "PIX charge",
+                        id: self.id.into_inner(),
+                    });
+                }
+            }
+        }
+        Err(result)
+    }
+}
+
+impl<'a, Id> RefundBuilder<'a, Id>
+where
+    Id: AsRef<str> + Serialize + std::fmt::Debug,
+{
+    fn new(client: &'a AbacatePay, path: &'static str, id: Id) -> Self {
+        RefundBuilder {
+            client,
+            path,
+            data: CreateRefundData {
+                id,
+                amount: None,
+                reason: None,
+            },
+            idempotency_key: format!("refund-{}", uuid::Uuid::new_v4()),
+        }
+    }
+
+    /// Refunds only `amount` cents instead of the full amount.
+    pub fn amount(mut self, amount: i64) -> Self {
+        self.data.amount = Some(amount);
+        self
+    }
+
+    pub fn reason(mut self, reason: String) -> Self {
+        self.data.reason = Some(reason);
+        self
+    }
+
+    /// Overrides the `Idempotency-Key` header.
+    ///
+    /// Pass the key of an earlier attempt to retry that same refund without refunding twice.
+    pub fn idempotency_key(mut self, key: String) -> Self {
+        self.idempotency_key = key;
+        self
+    }
+
+    /// The `Idempotency-Key` this refund will be sent with: a random key unique to this
+    /// builder (and its clones) unless overridden.
+    pub fn current_idempotency_key(&self) -> &str {
+        &self.idempotency_key
+    }
+
+    pub async fn build(self) -> Result<Refund, AbacatePayError> {
+        self.build_with_meta().await.map(|(refund, _)| refund)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
+    #[instrument(skip(self), fields(id = self.data.id.as_ref(), amount = ?self.data.amount))]
+    pub async fn build_with_meta(self) -> Result<(Refund, ResponseMeta), AbacatePayError> {
+        self.data.validate()?;
+
+        let idempotency_key = self.idempotency_key.clone();
+        let (result, meta): (RefundResponse, ResponseMeta) = self
             .client
+            .send(Method::POST, self.path, |request| {
+                request
+                    .header("Idempotency-Key", idempotency_key)
+                    .json(&self.data)
+            })
+            .await?;
+        match result {
+            RefundResponse::Success { data, .. } => {
+                debug!(refund_id = ?data.id, status = ?data.status, "Successfully created refund");
+                Ok((data, meta))
+            }
+            RefundResponse::Error {
+                error,
+                code,
+                message,
+            } => {
+                error!(
+                    error = error.as_str(),
+                    "API returned error in response body"
+                );
+                Err(AbacatePayError::ApiError {
+                    status: StatusCode::OK,
+                    code: code.into(),
+                    error,
+                    message,
+                    meta: Box::new(meta),
+                })
+            }
+        }
+    }
+}
+
+impl SimulatePixPaymentBuilder<'_> {
+    pub fn id(mut self, id: PixChargeId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Simulates the payment of the charge.
+    ///
+    /// Refused with `AbacatePayError::DevModeOnly`, before any request, unless the key is a
+    /// dev key (`abc_dev_...`); keys with an unrecognized prefix are refused too.
+    pub async fn build(self) -> Result<PixChargeData, AbacatePayError> {
+        self.build_with_meta().await.map(|(data, _)| data)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
+    #[instrument(skip(self))]
+    pub async fn build_with_meta(self) -> Result<(PixChargeData, ResponseMeta), AbacatePayError> {
+        if self.client.key_mode() != KeyMode::Dev {
+            return Err(AbacatePayError::DevModeOnly {
+                operation: "PIX payment simulation",
+            });
+        }
+
+        let (result, meta): (PixChargeResponse, ResponseMeta) = self
             .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.client.api_key))
-            .header(
-                "User-Agent",
-                format!("Rust SDK {}", env!("CARGO_PKG_VERSION")),
-            )
-            .query(&["id", &self.id])
-            .send()
+            .send(Method::POST, "pixQrCode/simulate-payment", |request| {
+                request.query(&[("id", self.id.as_str())])
+            })
             .await?;
-        let result: PixChargeResponse = self.client.handle_response(response).await?;
         match result {
             PixChargeResponse::Success { data, .. } => {
                 debug!(pix_charge_id = ?data.amount, "Successfully simulated PIX payment");
-                Ok(data)
+                Ok((data, meta))
             }
             PixChargeResponse::Error {
                 error,
@@ -265,9 +950,10 @@ impl SimulatePixPaymentBuilder<'_> {
                 );
                 Err(AbacatePayError::ApiError {
                     status: StatusCode::OK,
-                    error: error,
-                    message: message,
-                    code: code,
+                    code: code.into(),
+                    error,
+                    message,
+                    meta: Box::new(meta),
                 })
             }
         }
@@ -292,26 +978,25 @@ impl PixChargeBuilder<'_> {
         self
     }
 
-    #[instrument(skip(self))]
     pub async fn build(self) -> Result<PixChargeData, AbacatePayError> {
-        let url = format!("{}/pixQrCode/create", self.client.base_url);
-        let response = self
-            .client
+        self.build_with_meta().await.map(|(data, _)| data)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
+    #[instrument(skip(self))]
+    pub async fn build_with_meta(self) -> Result<(PixChargeData, ResponseMeta), AbacatePayError> {
+        self.data.validate()?;
+
+        let (result, meta): (PixChargeResponse, ResponseMeta) = self
             .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.client.api_key))
-            .header(
-                "User-Agent",
-                format!("Rust SDK {}", env!("CARGO_PKG_VERSION")),
-            )
-            .json(&self.data)
-            .send()
+            .send(Method::POST, "pixQrCode/create", |request| {
+                request.json(&self.data)
+            })
             .await?;
-        let result: PixChargeResponse = self.client.handle_response(response).await?;
         match result {
             PixChargeResponse::Success { data, .. } => {
                 debug!(pix_charge_id = ?data.amount, "Successfully created PIX charge");
-                Ok(data)
+                Ok((data, meta))
             }
             PixChargeResponse::Error {
                 error,
@@ -324,16 +1009,68 @@ impl PixChargeBuilder<'_> {
                 );
                 Err(AbacatePayError::ApiError {
                     status: StatusCode::OK,
-                    code,
+                    code: code.into(),
                     error,
                     message,
+                    meta: Box::new(meta),
                 })
             }
         }
     }
 }
 
-impl BillingBuilder<'_> {
+impl<'a, P, M, R, C> BillingBuilder<'a, P, M, R, C> {
+    fn transition<P2, M2, R2, C2>(self) -> BillingBuilder<'a, P2, M2, R2, C2> {
+        BillingBuilder {
+            client: self.client,
+            data: self.data,
+            state: PhantomData,
+        }
+    }
+
+    pub fn frequency(mut self, frequency: BillingKind) -> Self {
+        self.data.frequency = frequency;
+        self
+    }
+
+    pub fn method(mut self, method: BillingMethods) -> BillingBuilder<'a, P, Set, R, C> {
+        self.data.methods.push(method);
+        self.transition()
+    }
+
+    pub fn product(mut self, product: CreateBillingProduct) -> BillingBuilder<'a, Set, M, R, C> {
+        self.data.products.push(product);
+        self.transition()
+    }
+
+    pub fn return_url(mut self, url: String) -> BillingBuilder<'a, P, M, Set, C> {
+        self.data.return_url = url;
+        self.transition()
+    }
+
+    pub fn completion_url(mut self, url: String) -> BillingBuilder<'a, P, M, R, Set> {
+        self.data.completion_url = url;
+        self.transition()
+    }
+
+    pub fn customer_id(mut self, id: CustomerId) -> Self {
+        self.data.customer_id = Some(id);
+        self
+    }
+}
+
+impl BillingBuilder<'_, Set, Set, Set, Set> {
+    pub async fn build(self) -> Result<Billing, AbacatePayError> {
+        self.build_with_meta().await.map(|(billing, _)| billing)
+    }
+
+    /// Like [`build`](Self::build), also returning the response metadata.
+    pub async fn build_with_meta(self) -> Result<(Billing, ResponseMeta), AbacatePayError> {
+        self.client.send_create_billing(&self.data).await
+    }
+}
+
+impl UncheckedBillingBuilder<'_> {
     pub fn frequency(mut self, frequency: BillingKind) -> Self {
         self.data.frequency = frequency;
         self
@@ -359,58 +1096,18 @@ impl BillingBuilder<'_> {
         self
     }
 
-    pub fn customer_id(mut self, id: String) -> Self {
+    pub fn customer_id(mut self, id: CustomerId) -> Self {
         self.data.customer_id = Some(id);
         self
     }
 
-    #[instrument(skip(self))]
     pub async fn build(self) -> Result<Billing, AbacatePayError> {
-        let url = format!("{}/billing/create", self.client.base_url);
-
-        debug!(
-            url = url.as_str(),
-            request_data = ?self.data,
-            "Sending create billing request"
-        );
-
-        let response = self
-            .client
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.client.api_key))
-            .header(
-                "User-Agent",
-                format!("Rust SDK {}", env!("CARGO_PKG_VERSION")),
-            )
-            .json(&self.data)
-            .send()
-            .await?;
-
-        let result: CreateBillingResponse = self.client.handle_response(response).await?;
+        self.build_with_meta().await.map(|(billing, _)| billing)
+    }
 
-        match result {
-            CreateBillingResponse::Success { billing, .. } => {
-                debug!(billing_id = ?billing._id, "Successfully created billing");
-                Ok(billing)
-            }
-            CreateBillingResponse::Error {
-                error,
-                code,
-                message,
-            } => {
-                error!(
-                    error = error.as_str(),
-                    "API returned error in response body"
-                );
-                Err(AbacatePayError::ApiError {
-                    status: StatusCode::OK,
-                    code,
-                    error,
-                    message,
-                })
-            }
-        }
+    /// Like [`build`](Self::build), also returning the response metadata.
+    pub async fn build_with_meta(self) -> Result<(Billing, ResponseMeta), AbacatePayError> {
+        self.client.send_create_billing(&self.data).await
     }
 }
 
@@ -423,6 +1120,43 @@ mod tests {
         AbacatePay::new("my-key!".to_string())
     }
 
+    #[test]
+    async fn key_mode_guards() {
+        let dev = AbacatePay::new("abc_dev_123");
+        let prod = AbacatePay::new("abc_prod_123");
+
+        assert!(dev.is_dev_mode());
+        assert!(!prod.is_dev_mode());
+        assert!(matches!(
+            prod.create_simulate_pix_payment(PixChargeId::from("pix_char_1"))
+                .build()
+                .await,
+            Err(AbacatePayError::DevModeOnly { .. })
+        ));
+        assert!(matches!(
+            client()
+                .create_simulate_pix_payment(PixChargeId::from("pix_char_1"))
+                .build()
+                .await,
+            Err(AbacatePayError::DevModeOnly { .. })
+        ));
+        assert!(dev.deny_dev_key(false).is_ok());
+        assert!(matches!(
+            AbacatePay::new("abc_dev_123").deny_dev_key(true),
+            Err(AbacatePayError::DevKeyInRelease)
+        ));
+        assert!(prod.deny_dev_key(true).is_ok());
+    }
+
+    #[test]
+    async fn debug_hides_api_key() {
+        let debug = format!("{:?}", client().with_body_logging(BodyLogging::Off));
+
+        assert!(!debug.contains("my-key!"));
+        assert!(debug.contains("[REDACTED]"));
+        assert!(debug.contains("body_logging: Off"));
+    }
+
     #[test]
     async fn create_billing_builder() {
         let client = client();
@@ -431,11 +1165,14 @@ mod tests {
             .create_billing()
             .frequency(BillingKind::OneTime)
             .method(BillingMethods::Pix)
-            .customer_id("my-customer-id".to_string());
+            .customer_id(CustomerId::from("my-customer-id"));
 
         assert_eq!(builder.data.frequency, BillingKind::OneTime);
         assert_eq!(builder.data.methods, vec![BillingMethods::Pix]);
-        assert_eq!(builder.data.customer_id, Some("my-customer-id".to_string()));
+        assert_eq!(
+            builder.data.customer_id,
+            Some(CustomerId::from("my-customer-id"))
+        );
     }
 
     #[test]
@@ -447,6 +1184,21 @@ mod tests {
         assert_eq!(builder.data.methods, vec![BillingMethods::Pix]);
     }
 
+    #[test]
+    async fn mixed_payment_methods() {
+        let client = client();
+
+        let builder = client
+            .create_billing()
+            .method(BillingMethods::Pix)
+            .method(BillingMethods::Card);
+
+        assert_eq!(
+            builder.data.methods,
+            vec![BillingMethods::Pix, BillingMethods::Card]
+        );
+    }
+
     #[test]
     async fn billing_urls() {
         let client = client();
@@ -504,6 +1256,52 @@ mod tests {
         assert_eq!(builder.data.customer_id, None);
     }
 
+    #[test]
+    async fn complete_billing_builder() {
+        let client = client();
+
+        let product = CreateBillingProduct {
+            external_id: "external_id_1".to_string(),
+            name: "Product 1".to_string(),
+            price: 10.0,
+            quantity: 1,
+            description: None,
+        };
+
+        // Setters may be called in any order and repeated; the result is buildable.
+        let builder: BillingBuilder<'_, Set, Set, Set, Set> = client
+            .create_billing()
+            .completion_url("http://localhost:3030?success=true".to_string())
+            .method(BillingMethods::Pix)
+            .product(product.clone())
+            .return_url("http://localhost:3030".to_string())
+            .product(product.clone())
+            .method(BillingMethods::Pix);
+
+        assert_eq!(builder.data.products, vec![product.clone(), product]);
+        assert_eq!(
+            builder.data.methods,
+            vec![BillingMethods::Pix, BillingMethods::Pix]
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    async fn unchecked_billing_builder() {
+        let client = client();
+
+        let builder = client
+            .create_billing_unchecked()
+            .frequency(BillingKind::MultiplePayments)
+            .method(BillingMethods::Pix)
+            .customer_id(CustomerId::from("my-customer-id"));
+
+        assert_eq!(builder.data.frequency, BillingKind::MultiplePayments);
+        assert_eq!(builder.data.methods, vec![BillingMethods::Pix]);
+        assert!(builder.data.products.is_empty());
+        assert_eq!(builder.data.return_url, String::new());
+    }
+
     #[test]
     async fn create_pix_charge_builder() {
         let client = client();
@@ -555,15 +1353,571 @@ mod tests {
         assert!(builder.data.customer.is_none());
     }
 
+    #[test]
+    async fn pix_charge_build_validates_before_sending() {
+        let client = client();
+
+        let result = client.create_pix_charge().amount(0).build().await;
+
+        assert!(matches!(result, Err(AbacatePayError::Validation(_))));
+    }
+
+    fn list_billings_body() -> serde_json::Value {
+        let billing = |id: &str, status: &str, created_at: &str| {
+            serde_json::json!({
+                "metadata": { "fee": 80, "returnUrl": "http://localhost", "completionUrl": "http://localhost" },
+                "pId": id,
+                "publicId": id,
+                "products": [],
+                "amount": 1000,
+                "status": status,
+                "devMode": true,
+                "methods": ["PIX"],
+                "frequency": "ONE_TIME",
+                "createdAt": created_at,
+                "updateAt": created_at,
+                "__v": 0,
+                "url": "https://abacatepay.com/pay/bill",
+                "id": id,
+                "customerId": null,
+                "customer": null,
+            })
+        };
+
+        serde_json::json!({
+            "error": null,
+            "billings": [
+                billing("bill_1", "PAID", "2024-11-01T12:00:00Z"),
+                billing("bill_2", "PENDING", "2024-11-02T12:00:00Z"),
+                billing("bill_3", "PAID", "2024-11-03T12:00:00Z"),
+                billing("bill_4", "PAID", "2024-12-01T12:00:00Z"),
+            ],
+        })
+    }
+
+    #[test]
+    async fn query_billings_filters_and_pages() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/billing/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_billings_body()))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let billings = client
+            .query_billings()
+            .status(BillingStatus::PAID)
+            .created_until("2024-11-30T00:00:00Z".parse().unwrap())
+            .offset(1)
+            .limit(5)
+            .build()
+            .await
+            .unwrap();
+
+        let ids: Vec<_> = billings.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["bill_3"]);
+    }
+
+    #[test]
+    async fn query_billings_stream_is_lazy() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/billing/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_billings_body()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let stream = client.query_billings().limit(2).into_stream();
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        let ids: Vec<_> = stream.map(|b| b.unwrap().id.into_inner()).collect().await;
+        assert_eq!(ids, vec!["bill_1", "bill_2"]);
+    }
+
+    #[test]
+    async fn get_billing_by_id() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/billing/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_billings_body()))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let billing = client.get_billing(BillingId::from("bill_2")).await.unwrap();
+        let missing = client.get_billing(BillingId::from("bill_9")).await;
+
+        assert_eq!(billing.status, BillingStatus::PENDING);
+        assert!(matches!(
+            missing,
+            Err(AbacatePayError::NotFound { resource: "billing", id }) if id == "bill_9"
+        ));
+    }
+
+    fn pix_charge_body(status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "error": null,
+            "data": {
+                "id": "pix_char_123",
+                "amount": 1000,
+                "status": status,
+                "devMode": true,
+                "method": "PIX",
+                "brCode": "00020101021226950014br.gov.bcb.pix",
+                "brCodeBase64": "data:image/png;base64,iVBORw0KGgoAAA",
+                "platformFee": 80,
+                "createdAt": "2025-03-24T21:50:20.772Z",
+                "updatedAt": "2025-03-24T21:50:20.772Z",
+                "expiresAt": "2025-03-25T21:50:20.772Z",
+            },
+        })
+    }
+
+    #[test]
+    async fn errors_redact_response_bodies() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": null,
+                "data": { "status": 5, "email": "ana@example.com" },
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/create"))
+            .respond_with(
+                ResponseTemplate::new(502)
+                    .set_body_json(serde_json::json!({ "email": "ana@example.com" })),
+            )
+            .mount(&server)
+            .await;
+        let client = client().with_base_url(server.uri());
+
+        let parse = client
+            .check_pix_status(PixChargeId::from("pix_char_123"))
+            .build()
+            .await
+            .unwrap_err();
+        let unexpected = client
+            .create_pix_charge()
+            .amount(1000)
+            .build()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(parse, AbacatePayError::ParseError { .. }));
+        assert!(matches!(
+            unexpected,
+            AbacatePayError::UnexpectedResponse { .. }
+        ));
+        for error in [parse, unexpected] {
+            let text = error.to_string();
+            assert!(!text.contains("ana@example.com"), "{text}");
+            assert!(text.contains(crate::redact::REDACTED), "{text}");
+        }
+    }
+
+    #[test]
+    async fn response_meta_on_success_and_error() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/create"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("x-request-id", "req_ok")
+                    .insert_header("x-ratelimit-remaining", "41")
+                    .set_body_json(pix_charge_body("PENDING")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("x-request-id", "req_limited")
+                    .insert_header("retry-after", "5")
+                    .set_body_json(serde_json::json!({
+                        "error": "Too Many Requests",
+                        "message": "Slow down",
+                        "code": "RATE_LIMITED",
+                    })),
+            )
+            .mount(&server)
+            .await;
+        let client = AbacatePay::new("abc_dev_123").with_base_url(server.uri());
+
+        let (charge, meta) = client
+            .create_pix_charge()
+            .amount(1000)
+            .build_with_meta()
+            .await
+            .unwrap();
+        let error = client
+            .check_pix_status(PixChargeId::from("pix_char_123"))
+            .build()
+            .await
+            .unwrap_err();
+
+        assert_eq!(charge.id, PixChargeId::from("pix_char_123"));
+        assert_eq!(meta.status, StatusCode::OK);
+        assert_eq!(meta.request_id.as_deref(), Some("req_ok"));
+        assert_eq!(meta.rate_limit.remaining, Some(41));
+        assert!(matches!(error, AbacatePayError::ApiError { .. }));
+        assert_eq!(error.request_id(), Some("req_limited"));
+        assert_eq!(error.code(), Some(&crate::ErrorCode::RateLimited));
+        assert!(error.is_retryable());
+        assert_eq!(
+            error.meta().unwrap().rate_limit.retry_after,
+            Some(std::time::Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    async fn cancel_pix_charge() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/cancel"))
+            .and(query_param("id", "pix_char_123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(pix_charge_body("CANCELLED")))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let data = client
+            .cancel_pix_charge(PixChargeId::from("pix_char_123"))
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(data.status, PixStatus::CANCELLED);
+    }
+
+    #[test]
+    async fn cancel_paid_pix_charge() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/cancel"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "Bad request",
+                "message": "Charge cannot be cancelled",
+                "code": "INVALID_STATUS",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": null,
+                "data": { "status": "PAID", "expiresAt": "2025-03-25T21:50:20.772Z" },
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let result = client
+            .cancel_pix_charge(PixChargeId::from("pix_char_123"))
+            .build()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AbacatePayError::AlreadyPaid {
+                resource: "PIX charge",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    async fn cancel_billing_error_when_not_paid() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/billing/cancel"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "Bad request",
+                "message": "Billing cannot be cancelled",
+                "code": "INVALID_STATUS",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/billing/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_billings_body()))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let pending = client
+            .cancel_billing(BillingId::from("bill_2"))
+            .build()
+            .await;
+        let paid = client
+            .cancel_billing(BillingId::from("bill_1"))
+            .build()
+            .await;
+
+        assert!(matches!(pending, Err(AbacatePayError::ApiError { .. })));
+        assert!(matches!(
+            paid,
+            Err(AbacatePayError::AlreadyPaid {
+                resource: "billing",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    async fn cancel_billing_skips_lookup_for_other_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/billing/cancel"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": "Not found",
+                "message": "Billing not found",
+                "code": "NOT_FOUND",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/billing/list"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(list_billings_body()))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let result = client()
+            .with_base_url(server.uri())
+            .cancel_billing(BillingId::from("bill_1"))
+            .build()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AbacatePayError::ApiError {
+                code: ErrorCode::NotFound,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    async fn refund_builder_idempotency_key() {
+        let client = client();
+
+        let first = client
+            .refund_billing(BillingId::from("bill_123"))
+            .amount(500)
+            .reason("Damaged item".to_string());
+        let second = client
+            .refund_billing(BillingId::from("bill_123"))
+            .amount(500);
+        let explicit = client
+            .refund_billing(BillingId::from("bill_123"))
+            .amount(500)
+            .idempotency_key("my-key".to_string());
+
+        assert_ne!(
+            first.current_idempotency_key(),
+            second.current_idempotency_key()
+        );
+        assert!(first.current_idempotency_key().starts_with("refund-"));
+        assert_eq!(first.data.reason.as_deref(), Some("Damaged item"));
+        assert_eq!(explicit.current_idempotency_key(), "my-key");
+    }
+
+    #[test]
+    async fn refund_retry_reuses_idempotency_key() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/billing/refund"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/billing/refund"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": null,
+                "data": {
+                    "id": "refund_123",
+                    "amount": 500,
+                    "status": "COMPLETED",
+                    "devMode": true,
+                    "createdAt": "2025-03-24T21:50:20.772Z",
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let refund = client
+            .refund_billing(BillingId::from("bill_123"))
+            .amount(500);
+        let first = refund.clone().build().await;
+        assert!(first.unwrap_err().is_retryable());
+        refund.build().await.unwrap();
+
+        let keys: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| request.headers["idempotency-key"].clone())
+            .collect();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0], keys[1]);
+    }
+
+    #[test]
+    async fn partial_refund_request() {
+        use wiremock::matchers::{body_json, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let client = client().with_base_url(server.uri());
+        let key = client
+            .refund_pix_charge(PixChargeId::from("pix_char_123"))
+            .current_idempotency_key()
+            .to_string();
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/refund"))
+            .and(header("Idempotency-Key", key.as_str()))
+            .and(body_json(serde_json::json!({
+                "id": "pix_char_123",
+                "amount": 500,
+                "reason": "Customer request",
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": null,
+                "data": {
+                    "id": "refund_123",
+                    "amount": 500,
+                    "status": "COMPLETED",
+                    "reason": "Customer request",
+                    "devMode": true,
+                    "createdAt": "2025-03-24T21:50:20.772Z",
+                },
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        for _ in 0..2 {
+            let refund = client
+                .refund_pix_charge(PixChargeId::from("pix_char_123"))
+                .amount(500)
+                .reason("Customer request".to_string())
+                .idempotency_key(key.clone())
+                .build()
+                .await
+                .unwrap();
+
+            assert_eq!(refund.amount, 500);
+            assert_eq!(refund.status, crate::refund::RefundStatus::COMPLETED);
+        }
+    }
+
+    #[test]
+    async fn batch_check_pix_status_builder() {
+        let client = client();
+
+        let builder = client
+            .batch_check_pix_status(vec![PixChargeId::from("a"), PixChargeId::from("b")])
+            .concurrency(0);
+
+        assert_eq!(
+            builder.ids,
+            vec![PixChargeId::from("a"), PixChargeId::from("b")]
+        );
+        assert_eq!(builder.concurrency, 1);
+    }
+
+    #[test]
+    async fn batch_check_pix_status_keeps_going_after_failures() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .and(query_param("id", "missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": "Not found",
+                "message": "PIX charge not found",
+                "code": "NOT_FOUND",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": null,
+                "data": { "status": "PAID", "expiresAt": "2025-03-25T21:50:20.772Z" },
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client().with_base_url(server.uri());
+        let ids = ["first", "missing", "last"].map(PixChargeId::from);
+        let results = client
+            .batch_check_pix_status(ids.clone())
+            .concurrency(2)
+            .build()
+            .await;
+
+        let returned_ids: Vec<_> = results.iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(returned_ids, ids.to_vec());
+        assert!(results[0].1.is_ok());
+        assert!(matches!(
+            results[1].1,
+            Err(AbacatePayError::ApiError { status, .. }) if status == StatusCode::NOT_FOUND
+        ));
+        assert!(results[2].1.is_ok());
+    }
+
     #[test]
     async fn simulate_pix_payment_builder() {
         let client = client();
 
-        let builder = client.create_simulate_pix_payment("test-charge-id".to_string());
-        assert_eq!(builder.id, "test-charge-id");
+        let builder = client.create_simulate_pix_payment(PixChargeId::from("test-charge-id"));
+        assert_eq!(builder.id.as_str(), "test-charge-id");
 
         // Test the setter method
-        let builder = builder.id("new-test-id".to_string());
-        assert_eq!(builder.id, "new-test-id");
+        let builder = builder.id(PixChargeId::from("new-test-id"));
+        assert_eq!(builder.id.as_str(), "new-test-id");
     }
 }
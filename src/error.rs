@@ -1,6 +1,11 @@
+use std::fmt;
+
 use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::meta::ResponseMeta;
+
 #[derive(Error, Debug)]
 pub enum AbacatePayError {
     #[error("HTTP request failed: {0}")]
@@ -11,15 +16,384 @@ pub enum AbacatePayError {
         status: StatusCode,
         error: String,
         message: String,
-        code: String,
+        code: ErrorCode,
+        meta: Box<ResponseMeta>,
     },
 
+    /// `message` and `response` are redacted unless the client logs bodies with
+    /// `BodyLogging::Full`, so the error can be printed safely.
     #[error("Failed to parse API response: {message}. Response: {response}")]
-    ParseError { message: String, response: String },
+    ParseError {
+        message: String,
+        response: String,
+        meta: Box<ResponseMeta>,
+    },
 
+    /// `response` is redacted unless the client logs bodies with `BodyLogging::Full`.
     #[error("Unexpected response ({status}): {response}")]
     UnexpectedResponse {
         status: StatusCode,
         response: String,
+        meta: Box<ResponseMeta>,
     },
+
+    #[error("Invalid request: {}", join_field_errors(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("Operation was cancelled")]
+    Cancelled,
+
+    #[error("{resource} not found: {id}")]
+    NotFound { resource: &'static str, id: String },
+
+    #[error("{resource} {id} is already paid and cannot be cancelled")]
+    AlreadyPaid { resource: &'static str, id: String },
+
+    #[error("Invalid client configuration: {0}")]
+    Config(String),
+
+    #[error("{operation} is only available with a dev mode API key")]
+    DevModeOnly { operation: &'static str },
+
+    #[error("A dev mode API key cannot be used in a release build")]
+    DevKeyInRelease,
+}
+
+impl AbacatePayError {
+    /// Headers of the response that caused the error, when the API answered at all.
+    pub fn meta(&self) -> Option<&ResponseMeta> {
+        match self {
+            AbacatePayError::ApiError { meta, .. }
+            | AbacatePayError::ParseError { meta, .. }
+            | AbacatePayError::UnexpectedResponse { meta, .. } => Some(meta),
+            _ => None,
+        }
+    }
+
+    /// The request identifier to quote in support tickets, if the API sent one.
+    pub fn request_id(&self) -> Option<&str> {
+        self.meta()?.request_id.as_deref()
+    }
+
+    /// The API's error code, for errors the API reported.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        match self {
+            AbacatePayError::ApiError { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Whether the same request may succeed if sent again later: network failures,
+    /// timeouts, rate limiting and server errors.
+    ///
+    /// This does not mean resending is harmless: after a timeout or 5xx the API may have
+    /// carried out the request anyway. For calls without an idempotency key, such as
+    /// `create_pix_charge` or `create_billing`, check
+    /// [`is_safe_to_resend`](Self::is_safe_to_resend) instead to avoid duplicates.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AbacatePayError::RequestError(e) => {
+                !(e.is_builder() || e.is_redirect() || e.is_decode())
+            }
+            AbacatePayError::ApiError { status, code, .. } => {
+                is_retryable_status(*status)
+                    || matches!(code, ErrorCode::RateLimited | ErrorCode::InternalError)
+            }
+            AbacatePayError::UnexpectedResponse { status, .. } => is_retryable_status(*status),
+            _ => false,
+        }
+    }
+
+    /// Whether the API certainly did not act on the request, so sending it again cannot
+    /// duplicate anything: the connection could not be established, or the API rate-limited it.
+    pub fn is_safe_to_resend(&self) -> bool {
+        match self {
+            AbacatePayError::RequestError(e) => e.is_connect(),
+            AbacatePayError::ApiError { status, .. }
+            | AbacatePayError::UnexpectedResponse { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the request itself is at fault, so sending it again unchanged will fail
+    /// again: 4xx responses other than timeouts and rate limiting, requests rejected
+    /// before being sent, and operations on missing or already paid resources.
+    pub fn is_client_error(&self) -> bool {
+        match self {
+            AbacatePayError::ApiError { status, code, .. } => {
+                if status.is_success() {
+                    code.is_client_error()
+                } else {
+                    status.is_client_error() && !is_retryable_status(*status)
+                }
+            }
+            AbacatePayError::UnexpectedResponse { status, .. } => {
+                status.is_client_error() && !is_retryable_status(*status)
+            }
+            AbacatePayError::Validation(_)
+            | AbacatePayError::NotFound { .. }
+            | AbacatePayError::AlreadyPaid { .. }
+            | AbacatePayError::DevModeOnly { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the API key was rejected or lacks permission (401, 403 or an auth error code).
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            AbacatePayError::ApiError { status, code, .. } => {
+                is_auth_status(*status)
+                    || matches!(code, ErrorCode::Unauthorized | ErrorCode::Forbidden)
+            }
+            AbacatePayError::UnexpectedResponse { status, .. } => is_auth_status(*status),
+            _ => false,
+        }
+    }
+
+    /// A short, stable name for the kind of failure, used as a metric label and span attribute.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            AbacatePayError::RequestError(e) if e.is_timeout() => "timeout",
+            AbacatePayError::RequestError(e) if e.is_connect() => "connect",
+            AbacatePayError::RequestError(_) => "request",
+            AbacatePayError::ApiError { .. } => "api",
+            AbacatePayError::ParseError { .. } => "parse",
+            AbacatePayError::UnexpectedResponse { .. } => "unexpected_response",
+            AbacatePayError::Validation(_) => "validation",
+            AbacatePayError::Cancelled => "cancelled",
+            AbacatePayError::NotFound { .. } => "not_found",
+            AbacatePayError::AlreadyPaid { .. } => "already_paid",
+            AbacatePayError::Config(_) => "config",
+            AbacatePayError::DevModeOnly { .. } => "dev_mode_only",
+            AbacatePayError::DevKeyInRelease => "dev_key_in_release",
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+fn is_auth_status(status: StatusCode) -> bool {
+    status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
+}
+
+/// The `code` of an error reported by the API.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The API key is missing or invalid.
+    Unauthorized,
+    /// The API key may not perform this operation.
+    Forbidden,
+    NotFound,
+    /// The request body was rejected.
+    ValidationError,
+    /// The resource's status does not allow the operation, e.g. cancelling a paid charge.
+    InvalidStatus,
+    RateLimited,
+    InternalError,
+    /// A code not yet known to this version of the SDK.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::ValidationError => "VALIDATION_ERROR",
+            ErrorCode::InvalidStatus => "INVALID_STATUS",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::Unknown(code) => code,
+        }
+    }
+
+    fn is_client_error(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::Unauthorized
+                | ErrorCode::Forbidden
+                | ErrorCode::NotFound
+                | ErrorCode::ValidationError
+                | ErrorCode::InvalidStatus
+        )
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        serde_json::from_value(serde_json::Value::String(code.clone()))
+            .unwrap_or(ErrorCode::Unknown(code))
+    }
+}
+
+impl From<&str> for ErrorCode {
+    fn from(code: &str) -> Self {
+        Self::from(code.to_string())
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single invalid field found while validating a request before sending it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Turns the field errors collected by a `validate()` implementation into its result.
+pub(crate) fn validation_result(errors: Vec<FieldError>) -> Result<(), AbacatePayError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AbacatePayError::Validation(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(status: StatusCode, code: &str) -> AbacatePayError {
+        AbacatePayError::ApiError {
+            status,
+            error: "error".to_string(),
+            message: "message".to_string(),
+            code: code.into(),
+            meta: Box::new(ResponseMeta {
+                status,
+                request_id: None,
+                rate_limit: Default::default(),
+            }),
+        }
+    }
+
+    #[test]
+    fn parses_known_and_unknown_codes() {
+        assert_eq!(ErrorCode::from("RATE_LIMITED"), ErrorCode::RateLimited);
+        assert_eq!(ErrorCode::from("NOT_FOUND"), ErrorCode::NotFound);
+        assert_eq!(
+            ErrorCode::from("SOMETHING_NEW"),
+            ErrorCode::Unknown("SOMETHING_NEW".to_string())
+        );
+        assert_eq!(ErrorCode::InvalidStatus.to_string(), "INVALID_STATUS");
+        assert_eq!(
+            serde_json::from_str::<ErrorCode>(r#""UNAUTHORIZED""#).unwrap(),
+            ErrorCode::Unauthorized
+        );
+    }
+
+    #[test]
+    fn classifies_api_errors() {
+        let rate_limited = api_error(StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED");
+        assert!(rate_limited.is_retryable());
+        assert!(!rate_limited.is_client_error());
+        assert_eq!(rate_limited.code(), Some(&ErrorCode::RateLimited));
+
+        let unauthorized = api_error(StatusCode::UNAUTHORIZED, "UNAUTHORIZED");
+        assert!(unauthorized.is_auth_error());
+        assert!(unauthorized.is_client_error());
+        assert!(!unauthorized.is_retryable());
+
+        let in_body = api_error(StatusCode::OK, "INVALID_STATUS");
+        assert!(in_body.is_client_error());
+        assert!(!in_body.is_retryable());
+        assert!(!in_body.is_auth_error());
+
+        let unknown_in_body = api_error(StatusCode::OK, "SOMETHING_NEW");
+        assert!(!unknown_in_body.is_client_error());
+        assert!(!unknown_in_body.is_retryable());
+
+        let server = api_error(StatusCode::BAD_GATEWAY, "SOMETHING_NEW");
+        assert!(server.is_retryable());
+        assert!(!server.is_client_error());
+        assert!(!server.is_safe_to_resend());
+        assert!(rate_limited.is_safe_to_resend());
+    }
+
+    #[tokio::test]
+    async fn classifies_request_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/create"))
+            .respond_with(
+                ResponseTemplate::new(307)
+                    .insert_header("location", format!("{}/pixQrCode/create", server.uri())),
+            )
+            .mount(&server)
+            .await;
+
+        let redirect = crate::AbacatePay::new("abc_dev_123")
+            .with_base_url(server.uri())
+            .create_pix_charge()
+            .amount(1000)
+            .build()
+            .await
+            .unwrap_err();
+        let connect = crate::AbacatePay::new("abc_dev_123")
+            .with_base_url("http://127.0.0.1:1")
+            .create_pix_charge()
+            .amount(1000)
+            .build()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(&redirect, AbacatePayError::RequestError(e) if e.is_redirect()));
+        assert!(!redirect.is_retryable());
+        assert!(!redirect.is_safe_to_resend());
+        assert!(matches!(&connect, AbacatePayError::RequestError(e) if e.is_connect()));
+        assert!(connect.is_retryable());
+        assert!(connect.is_safe_to_resend());
+    }
+
+    #[test]
+    fn classifies_local_errors() {
+        let validation = AbacatePayError::Validation(vec![FieldError::new("amount", "required")]);
+        assert!(validation.is_client_error());
+        assert!(!validation.is_retryable());
+        assert_eq!(validation.code(), None);
+
+        assert!(!AbacatePayError::Cancelled.is_client_error());
+        assert!(!AbacatePayError::Cancelled.is_retryable());
+        assert!(!AbacatePayError::Config("bad".to_string()).is_auth_error());
+    }
 }
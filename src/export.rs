@@ -0,0 +1,427 @@
+//! Export of billings to CSV or JSON Lines, e.g. for spreadsheets.
+//!
+//! Feed it the `into_stream()` of [`AbacatePay::query_billings`](crate::AbacatePay::query_billings)
+//! so filters such as the creation date range apply before anything is written.
+
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use futures::{pin_mut, Stream, StreamExt};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::billing::{Billing, CustomerMetadata};
+use crate::AbacatePayError;
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error(transparent)]
+    Api(#[from] AbacatePayError),
+
+    #[error("Failed to write export: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to encode export: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row.
+    #[default]
+    Csv,
+    /// One JSON object per line, keyed by column name.
+    JsonLines,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" | "json-lines" | "ndjson" => Ok(ExportFormat::JsonLines),
+            other => Err(format!(
+                "unknown format `{other}`, expected `csv` or `jsonl`"
+            )),
+        }
+    }
+}
+
+/// A field of [`Billing`] that can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    Status,
+    /// The amount in reais with two decimals, e.g. `1234.50`.
+    Amount,
+    Frequency,
+    Methods,
+    DevMode,
+    CustomerId,
+    CustomerName,
+    CustomerEmail,
+    CustomerTaxId,
+    CreatedAt,
+    UpdatedAt,
+    Url,
+}
+
+impl Column {
+    pub const ALL: [Column; 13] = [
+        Column::Id,
+        Column::Status,
+        Column::Amount,
+        Column::Frequency,
+        Column::Methods,
+        Column::DevMode,
+        Column::CustomerId,
+        Column::CustomerName,
+        Column::CustomerEmail,
+        Column::CustomerTaxId,
+        Column::CreatedAt,
+        Column::UpdatedAt,
+        Column::Url,
+    ];
+
+    /// The columns exported when none are selected.
+    pub const DEFAULT: [Column; 7] = [
+        Column::Id,
+        Column::Status,
+        Column::Amount,
+        Column::Frequency,
+        Column::Methods,
+        Column::CustomerEmail,
+        Column::CreatedAt,
+    ];
+
+    /// The header and JSON key of the column, e.g. `customer_email`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Status => "status",
+            Column::Amount => "amount",
+            Column::Frequency => "frequency",
+            Column::Methods => "methods",
+            Column::DevMode => "dev_mode",
+            Column::CustomerId => "customer_id",
+            Column::CustomerName => "customer_name",
+            Column::CustomerEmail => "customer_email",
+            Column::CustomerTaxId => "customer_tax_id",
+            Column::CreatedAt => "created_at",
+            Column::UpdatedAt => "updated_at",
+            Column::Url => "url",
+        }
+    }
+
+    /// Like [`value`](Self::value), with customer-supplied text that a spreadsheet would
+    /// evaluate as a formula prefixed with `'`.
+    fn csv_value(self, billing: &Billing) -> String {
+        let value = self.value(billing);
+        match self {
+            Column::CustomerName | Column::CustomerEmail | Column::CustomerTaxId
+                if value.starts_with(['=', '+', '-', '@', '\t', '\r']) =>
+            {
+                format!("'{value}")
+            }
+            _ => value,
+        }
+    }
+
+    fn value(self, billing: &Billing) -> String {
+        match self {
+            Column::Id => billing.id.to_string(),
+            Column::Status => label(&billing.status),
+            Column::Amount => reais(billing.amount),
+            Column::Frequency => label(&billing.frequency),
+            Column::Methods => billing
+                .methods
+                .iter()
+                .map(label)
+                .collect::<Vec<_>>()
+                .join("|"),
+            Column::DevMode => billing.dev_mode.to_string(),
+            Column::CustomerId => billing
+                .customer
+                .as_ref()
+                .map(|customer| customer._id.to_string())
+                .or_else(|| {
+                    billing
+                        .customer_id
+                        .as_ref()
+                        .map(|details| details.public_id.to_string())
+                })
+                .unwrap_or_default(),
+            Column::CustomerName => customer(billing, |metadata| &metadata.name),
+            Column::CustomerEmail => customer(billing, |metadata| &metadata.email),
+            Column::CustomerTaxId => customer(billing, |metadata| &metadata.tax_id),
+            Column::CreatedAt => billing.created_at.to_rfc3339(),
+            Column::UpdatedAt => billing.update_at.to_rfc3339(),
+            Column::Url => billing.url.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_ascii_lowercase().replace('-', "_");
+        Column::ALL
+            .into_iter()
+            .find(|column| column.name() == value)
+            .ok_or_else(|| {
+                let names: Vec<_> = Column::ALL.iter().map(|column| column.name()).collect();
+                format!(
+                    "unknown column `{value}`, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Columns in output order; empty means [`Column::DEFAULT`].
+    pub columns: Vec<Column>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::default(),
+            columns: Column::DEFAULT.to_vec(),
+        }
+    }
+}
+
+/// Writes every billing of `billings` to `writer` as it arrives, returning how many were written.
+///
+/// Stops at the first error; rows written before it are left in `writer`.
+pub async fn write_billings<S, W>(
+    billings: S,
+    mut writer: W,
+    options: &ExportOptions,
+) -> Result<usize, ExportError>
+where
+    S: Stream<Item = Result<Billing, AbacatePayError>>,
+    W: Write,
+{
+    let columns = if options.columns.is_empty() {
+        &Column::DEFAULT[..]
+    } else {
+        &options.columns[..]
+    };
+
+    if options.format == ExportFormat::Csv {
+        let header: Vec<_> = columns.iter().map(|column| column.name()).collect();
+        writeln!(writer, "{}", header.join(","))?;
+    }
+
+    pin_mut!(billings);
+    let mut written = 0;
+    while let Some(billing) = billings.next().await {
+        let billing = billing?;
+        match options.format {
+            ExportFormat::Csv => {
+                let row: Vec<_> = columns
+                    .iter()
+                    .map(|column| csv_field(&column.csv_value(&billing)))
+                    .collect();
+                writeln!(writer, "{}", row.join(","))?;
+            }
+            ExportFormat::JsonLines => {
+                let object: serde_json::Map<_, _> = columns
+                    .iter()
+                    .map(|column| (column.name().to_string(), column.value(&billing).into()))
+                    .collect();
+                serde_json::to_writer(&mut writer, &object)?;
+                writeln!(writer)?;
+            }
+        }
+        written += 1;
+    }
+
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Formats an amount in cents as reais with two decimals, e.g. `1234.50`.
+fn reais(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{sign}{}.{:02}", cents / 100, cents % 100)
+}
+
+fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(label)) => label,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+fn customer(billing: &Billing, field: impl Fn(&CustomerMetadata) -> &String) -> String {
+    billing
+        .customer
+        .as_ref()
+        .map(|customer| &customer.metadata)
+        .or_else(|| {
+            billing
+                .customer_id
+                .as_ref()
+                .map(|details| &details.metadata)
+        })
+        .map(|metadata| field(metadata).clone())
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use serde_json::json;
+
+    fn billing(id: &str, amount: i64, email: &str) -> Billing {
+        serde_json::from_value(json!({
+            "metadata": { "fee": 80, "returnUrl": "https://example.com", "completionUrl": "https://example.com/done" },
+            "pId": id,
+            "publicId": id,
+            "products": [],
+            "amount": amount,
+            "status": "PAID",
+            "devMode": true,
+            "methods": ["PIX", "CARD"],
+            "frequency": "ONE_TIME",
+            "createdAt": "2024-11-04T18:38:28.573Z",
+            "updateAt": "2024-11-04T18:38:28.573Z",
+            "__v": 0,
+            "url": "https://pay.abacatepay.com/pay/bill_1",
+            "id": id,
+            "customer": {
+                "_id": "cust_1",
+                "metadata": { "name": "Ana, Silva", "cellphone": "", "taxId": "", "email": email }
+            }
+        }))
+        .unwrap()
+    }
+
+    async fn export(billings: Vec<Billing>, options: &ExportOptions) -> String {
+        let mut out = Vec::new();
+        let count = write_billings(
+            stream::iter(billings.into_iter().map(Ok)),
+            &mut out,
+            options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[tokio::test]
+    async fn writes_csv_with_selected_columns() {
+        let options = ExportOptions {
+            format: ExportFormat::Csv,
+            columns: vec![
+                Column::Id,
+                Column::Amount,
+                Column::Methods,
+                Column::CustomerName,
+            ],
+        };
+
+        let csv = export(
+            vec![
+                billing("bill_1", 123450, "a@x.com"),
+                billing("bill_2", 5, "b@x.com"),
+            ],
+            &options,
+        )
+        .await;
+
+        assert_eq!(
+            csv,
+            "id,amount,methods,customer_name\n\
+             bill_1,1234.50,PIX|CARD,\"Ana, Silva\"\n\
+             bill_2,0.05,PIX|CARD,\"Ana, Silva\"\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_json_lines() {
+        let options = ExportOptions {
+            format: ExportFormat::JsonLines,
+            columns: vec![Column::Id, Column::Status, Column::CustomerEmail],
+        };
+
+        let jsonl = export(
+            vec![
+                billing("bill_1", 100, "a@x.com"),
+                billing("bill_2", 200, "b@x.com"),
+            ],
+            &options,
+        )
+        .await;
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                json!({ "id": "bill_1", "status": "PAID", "customer_email": "a@x.com" }),
+                json!({ "id": "bill_2", "status": "PAID", "customer_email": "b@x.com" }),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn neutralizes_formulas_in_customer_cells() {
+        let options = ExportOptions {
+            format: ExportFormat::Csv,
+            columns: vec![Column::Amount, Column::CustomerEmail],
+        };
+
+        let csv = export(
+            vec![
+                billing("bill_1", -100, "=HYPERLINK(\"http://evil\")"),
+                billing("bill_2", 100, "@SUM(1+1)"),
+            ],
+            &options,
+        )
+        .await;
+
+        assert_eq!(
+            csv,
+            "amount,customer_email\n\
+             -1.00,\"'=HYPERLINK(\"\"http://evil\"\")\"\n\
+             1.00,'@SUM(1+1)\n"
+        );
+    }
+
+    #[test]
+    fn parses_columns_and_formats() {
+        assert_eq!("customer-email".parse(), Ok(Column::CustomerEmail));
+        assert!("price".parse::<Column>().is_err());
+        assert_eq!("JSONL".parse(), Ok(ExportFormat::JsonLines));
+        assert_eq!(reais(-1999), "-19.99");
+    }
+}
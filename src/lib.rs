@@ -1,7 +1,26 @@
+mod api_key;
+pub mod brcode;
 mod client;
 mod error;
+pub mod export;
+mod meta;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod models;
+mod polling;
+#[cfg(feature = "qrcode")]
+pub mod qr;
+mod redact;
+#[cfg(feature = "opentelemetry")]
+mod telemetry;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
+pub use api_key::{ApiKey, KeyMode, API_KEY_ENV};
 pub use client::AbacatePay;
-pub use error::AbacatePayError;
+pub use error::{AbacatePayError, ErrorCode, FieldError};
+pub use meta::{RateLimit, ResponseMeta};
 pub use models::*;
+pub use polling::PollOptions;
+pub use redact::BodyLogging;
+pub use tokio_util::sync::CancellationToken;
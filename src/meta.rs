@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+/// Headers identifying the request on AbacatePay's side, in order of preference.
+const REQUEST_ID_HEADERS: &[&str] = &["x-request-id", "request-id", "x-amzn-requestid"];
+
+/// Details of an API response beyond its body, available on success through the
+/// `*_with_meta` methods and on failure through [`AbacatePayError::meta`](crate::AbacatePayError::meta).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseMeta {
+    pub status: StatusCode,
+    /// Identifier of the request, to quote when contacting AbacatePay support.
+    pub request_id: Option<String>,
+    pub rate_limit: RateLimit,
+}
+
+/// Rate-limit headers of a response; each is `None` when the API did not send it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed in the current window (`X-RateLimit-Limit`).
+    pub limit: Option<u64>,
+    /// Requests left in the current window (`X-RateLimit-Remaining`).
+    pub remaining: Option<u64>,
+    /// Seconds until the window resets (`X-RateLimit-Reset`).
+    pub reset: Option<u64>,
+    /// How long to wait before retrying (`Retry-After`, in seconds).
+    pub retry_after: Option<Duration>,
+}
+
+impl ResponseMeta {
+    pub(crate) fn from_headers(status: StatusCode, headers: &HeaderMap) -> Self {
+        let text = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let number = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| text(name))
+                .and_then(|value| value.trim().parse().ok())
+        };
+
+        Self {
+            status,
+            request_id: REQUEST_ID_HEADERS
+                .iter()
+                .find_map(|name| text(name))
+                .map(str::to_string),
+            rate_limit: RateLimit {
+                limit: number(&["x-ratelimit-limit", "ratelimit-limit"]),
+                remaining: number(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+                reset: number(&["x-ratelimit-reset", "ratelimit-reset"]),
+                retry_after: number(&["retry-after"]).map(Duration::from_secs),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn reads_request_id_and_rate_limit() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", HeaderValue::from_static("req_123"));
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("100"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("soon"));
+
+        let meta = ResponseMeta::from_headers(StatusCode::TOO_MANY_REQUESTS, &headers);
+
+        assert_eq!(
+            meta,
+            ResponseMeta {
+                status: StatusCode::TOO_MANY_REQUESTS,
+                request_id: Some("req_123".to_string()),
+                rate_limit: RateLimit {
+                    limit: Some(100),
+                    remaining: Some(0),
+                    reset: None,
+                    retry_after: Some(Duration::from_secs(30)),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn missing_headers() {
+        let meta = ResponseMeta::from_headers(StatusCode::OK, &HeaderMap::new());
+
+        assert_eq!(meta.request_id, None);
+        assert_eq!(meta.rate_limit, RateLimit::default());
+    }
+}
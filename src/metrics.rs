@@ -0,0 +1,235 @@
+//! Request metrics (requires the `metrics` feature).
+//!
+//! Every API call is reported to the globally installed [`metrics`](::metrics) recorder, e.g.
+//! a Prometheus exporter:
+//!
+//! - [`REQUESTS_TOTAL`]: counter labelled `endpoint`, `method`, `status` and `error`
+//! - [`REQUEST_DURATION_SECONDS`]: histogram labelled `endpoint`, `method` and `status`
+//!
+//! `endpoint` is the API path such as `pixQrCode/create`, `status` the HTTP status code or
+//! `none` when no response arrived, and `error` one of `none`, `timeout`, `connect`,
+//! `request`, `api`, `parse` or `unexpected_response`. Errors the API reports in the body of
+//! a 2xx response count as `api`.
+
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode};
+
+use crate::AbacatePayError;
+
+/// Counter of API calls.
+pub const REQUESTS_TOTAL: &str = "abacatepay_requests_total";
+
+/// Histogram of API call latency in seconds, including reading the response body.
+pub const REQUEST_DURATION_SECONDS: &str = "abacatepay_request_duration_seconds";
+
+pub(crate) fn record_request<T>(
+    method: &Method,
+    endpoint: &'static str,
+    status: Option<StatusCode>,
+    result: &Result<T, AbacatePayError>,
+    elapsed: Duration,
+) {
+    let status = status.map_or_else(|| "none".to_string(), |status| status.as_u16().to_string());
+    let error = result.as_ref().err().map_or("none", AbacatePayError::kind);
+
+    ::metrics::counter!(
+        REQUESTS_TOTAL,
+        "endpoint" => endpoint,
+        "method" => method.to_string(),
+        "status" => status.clone(),
+        "error" => error,
+    )
+    .increment(1);
+    ::metrics::histogram!(
+        REQUEST_DURATION_SECONDS,
+        "endpoint" => endpoint,
+        "method" => method.to_string(),
+        "status" => status,
+    )
+    .record(elapsed.as_secs_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::PixChargeId;
+    use crate::AbacatePay;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    type Labels = Vec<(String, String)>;
+
+    /// Runs `calls` against a mock API on this thread with a local recorder, returning
+    /// `(metric name, labels, value)` for every metric recorded.
+    fn record<F, Fut>(calls: F) -> Vec<(String, Labels, DebugValue)>
+    where
+        F: FnOnce(AbacatePay) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(async {
+                    let server = MockServer::start().await;
+                    Mock::given(method("POST"))
+                        .and(path("/pixQrCode/check"))
+                        .respond_with(ResponseTemplate::new(503).set_body_string("down"))
+                        .mount(&server)
+                        .await;
+                    Mock::given(method("POST"))
+                        .and(path("/pixQrCode/create"))
+                        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                            "error": "Invalid amount",
+                            "message": "Amount must be positive",
+                            "code": "VALIDATION_ERROR",
+                        })))
+                        .mount(&server)
+                        .await;
+                    Mock::given(method("GET"))
+                        .and(path("/billing/list"))
+                        .respond_with(
+                            ResponseTemplate::new(200)
+                                .set_body_string(r#"{"billings":[],"error":null}"#),
+                        )
+                        .mount(&server)
+                        .await;
+
+                    calls(AbacatePay::new("abc_dev_123").with_base_url(server.uri())).await;
+                })
+        });
+
+        let mut metrics: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let (kind, key) = (key.kind(), key.key());
+                let mut labels: Vec<_> = key
+                    .labels()
+                    .map(|label| (label.key().to_string(), label.value().to_string()))
+                    .collect();
+                labels.sort();
+                assert!(matches!(kind, MetricKind::Counter | MetricKind::Histogram));
+                (key.name().to_string(), labels, value)
+            })
+            .collect();
+        metrics.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        metrics
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn records_errors_in_ok_bodies() {
+        let metrics = record(|client| async move {
+            let result = client.create_pix_charge().amount(1000).build().await;
+            assert!(matches!(result, Err(AbacatePayError::ApiError { .. })));
+        });
+
+        let counters: Vec<_> = metrics
+            .iter()
+            .filter(|(name, _, _)| name == REQUESTS_TOTAL)
+            .map(|(_, labels, _)| labels.clone())
+            .collect();
+        assert_eq!(
+            counters,
+            vec![labels(&[
+                ("endpoint", "pixQrCode/create"),
+                ("error", "api"),
+                ("method", "POST"),
+                ("status", "200"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn records_requests_per_endpoint() {
+        let metrics = record(|client| async move {
+            client.list_billings().await.unwrap();
+            client.list_billings().await.unwrap();
+            let _ = client
+                .check_pix_status(PixChargeId::from("pix_char_1"))
+                .build()
+                .await;
+        });
+
+        let counters: Vec<_> = metrics
+            .iter()
+            .filter(|(name, _, _)| name == REQUESTS_TOTAL)
+            .map(|(_, labels, value)| {
+                let DebugValue::Counter(count) = value else {
+                    panic!("expected a counter");
+                };
+                (labels.clone(), *count)
+            })
+            .collect();
+        assert_eq!(
+            counters,
+            vec![
+                (
+                    labels(&[
+                        ("endpoint", "billing/list"),
+                        ("error", "none"),
+                        ("method", "GET"),
+                        ("status", "200"),
+                    ]),
+                    2,
+                ),
+                (
+                    labels(&[
+                        ("endpoint", "pixQrCode/check"),
+                        ("error", "unexpected_response"),
+                        ("method", "POST"),
+                        ("status", "503"),
+                    ]),
+                    1,
+                ),
+            ]
+        );
+
+        let histograms: Vec<_> = metrics
+            .iter()
+            .filter(|(name, _, _)| name == REQUEST_DURATION_SECONDS)
+            .map(|(_, labels, value)| {
+                let DebugValue::Histogram(samples) = value else {
+                    panic!("expected a histogram");
+                };
+                (labels.clone(), samples.len())
+            })
+            .collect();
+        assert_eq!(
+            histograms,
+            vec![
+                (
+                    labels(&[
+                        ("endpoint", "billing/list"),
+                        ("method", "GET"),
+                        ("status", "200"),
+                    ]),
+                    2,
+                ),
+                (
+                    labels(&[
+                        ("endpoint", "pixQrCode/check"),
+                        ("method", "POST"),
+                        ("status", "503"),
+                    ]),
+                    1,
+                ),
+            ]
+        );
+    }
+}
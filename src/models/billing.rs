@@ -1,18 +1,33 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+use super::ids::{BillingId, CustomerId};
+use crate::error::{validation_result, AbacatePayError, FieldError};
+
+/// Longest product description, in characters, accepted by `CreateBillingData::validate`.
+pub const MAX_PRODUCT_DESCRIPTION_LENGTH: usize = 255;
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub enum BillingStatus {
     PENDING,
     EXPIRED,
     CANCELLED,
     PAID,
     REFUNDED,
+    /// A status not yet known to this version of the SDK.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub enum BillingMethods {
+    #[serde(rename = "PIX", alias = "Pix")]
     Pix,
+    #[serde(rename = "CARD", alias = "Card")]
+    Card,
+    /// A payment method not yet known to this version of the SDK.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
@@ -21,9 +36,12 @@ pub enum BillingKind {
     OneTime,
     #[serde(rename = "MULTIPLE_PAYMENTS")]
     MultiplePayments,
+    /// A billing frequency not yet known to this version of the SDK.
+    #[serde(untagged)]
+    Unknown(String),
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Metadata {
     pub fee: i64,
@@ -31,7 +49,7 @@ pub struct Metadata {
     pub completion_url: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Product {
     pub product_id: String,
@@ -47,32 +65,33 @@ pub struct CustomerMetadata {
     pub email: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct CustomerId {
+pub struct CustomerDetails {
     pub metadata: CustomerMetadata,
-    pub _id: String,
-    pub public_id: String,
+    pub _id: CustomerId,
+    pub public_id: CustomerId,
     pub store_id: String,
     pub dev_mode: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "__v")]
     pub __v: i64,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct Customer {
-    pub _id: String,
+    pub _id: CustomerId,
     pub metadata: CustomerMetadata,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Billing {
     pub metadata: Metadata,
     #[serde(rename = "pId")]
-    pub _id: String,
-    pub public_id: String,
+    pub _id: BillingId,
+    pub public_id: BillingId,
     pub products: Vec<Product>,
     pub amount: i64,
     pub status: BillingStatus,
@@ -81,10 +100,11 @@ pub struct Billing {
     pub frequency: BillingKind,
     pub created_at: DateTime<Utc>,
     pub update_at: DateTime<Utc>,
+    #[serde(rename = "__v")]
     pub __v: i64,
     pub url: String,
-    pub id: String,
-    pub customer_id: Option<CustomerId>,
+    pub id: BillingId,
+    pub customer_id: Option<CustomerDetails>,
     pub customer: Option<Customer>,
 }
 
@@ -106,11 +126,117 @@ pub struct CreateBillingData {
     pub products: Vec<CreateBillingProduct>,
     pub return_url: String,
     pub completion_url: String,
-    pub customer_id: Option<String>,
+    pub customer_id: Option<CustomerId>,
+}
+
+/// Client-side criteria for selecting billings returned by `billing/list`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BillingFilter {
+    /// Keep billings in any of these statuses; empty keeps all.
+    pub statuses: Vec<BillingStatus>,
+    /// Keep billings created at or after this instant.
+    pub created_from: Option<DateTime<Utc>>,
+    /// Keep billings created strictly before this instant.
+    pub created_until: Option<DateTime<Utc>>,
+    pub customer_id: Option<CustomerId>,
+    pub frequency: Option<BillingKind>,
+}
+
+impl BillingFilter {
+    pub fn matches(&self, billing: &Billing) -> bool {
+        if !self.statuses.is_empty() && !self.statuses.contains(&billing.status) {
+            return false;
+        }
+        if self
+            .created_from
+            .is_some_and(|from| billing.created_at < from)
+        {
+            return false;
+        }
+        if self
+            .created_until
+            .is_some_and(|until| billing.created_at >= until)
+        {
+            return false;
+        }
+        if let Some(customer_id) = &self.customer_id {
+            let customer = billing.customer.as_ref().map(|c| &c._id);
+            let details = billing.customer_id.as_ref().map(|c| &c._id);
+            if customer != Some(customer_id) && details != Some(customer_id) {
+                return false;
+            }
+        }
+        if let Some(frequency) = &self.frequency {
+            if &billing.frequency != frequency {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl CreateBillingData {
+    /// Checks the request locally, returning every invalid field at once.
+    pub fn validate(&self) -> Result<(), AbacatePayError> {
+        let mut errors = Vec::new();
+
+        if self.methods.is_empty() {
+            errors.push(FieldError::new(
+                "methods",
+                "at least one payment method is required",
+            ));
+        }
+        if self.products.is_empty() {
+            errors.push(FieldError::new(
+                "products",
+                "at least one product is required",
+            ));
+        }
+        for (index, product) in self.products.iter().enumerate() {
+            if product.quantity < 1 {
+                errors.push(FieldError::new(
+                    format!("products[{index}].quantity"),
+                    "must be at least 1",
+                ));
+            }
+            if !product.price.is_finite() || product.price <= 0.0 {
+                errors.push(FieldError::new(
+                    format!("products[{index}].price"),
+                    "must be a positive number",
+                ));
+            }
+            if let Some(description) = &product.description {
+                if description.trim().is_empty() {
+                    errors.push(FieldError::new(
+                        format!("products[{index}].description"),
+                        "must not be empty; omit it instead",
+                    ));
+                } else if description.chars().count() > MAX_PRODUCT_DESCRIPTION_LENGTH {
+                    errors.push(FieldError::new(
+                        format!("products[{index}].description"),
+                        format!("must be at most {MAX_PRODUCT_DESCRIPTION_LENGTH} characters"),
+                    ));
+                }
+            }
+        }
+        validate_url("return_url", &self.return_url, &mut errors);
+        validate_url("completion_url", &self.completion_url, &mut errors);
+
+        validation_result(errors)
+    }
+}
+
+fn validate_url(field: &str, value: &str, errors: &mut Vec<FieldError>) {
+    match reqwest::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        Ok(_) => errors.push(FieldError::new(field, "must be an http or https URL")),
+        Err(e) => errors.push(FieldError::new(field, format!("invalid URL: {e}"))),
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum CreateBillingResponse {
     Success {
         error: Option<()>,
@@ -136,3 +262,234 @@ pub enum ListBillingResponse {
         code: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn billing_json(status: &str, method: &str, frequency: &str) -> String {
+        format!(
+            r#"{{
+                "metadata": {{ "fee": 80, "returnUrl": "http://localhost", "completionUrl": "http://localhost" }},
+                "pId": "bill_123",
+                "publicId": "bill_123",
+                "products": [{{ "productId": "prod_123", "quantity": 1 }}],
+                "amount": 1000,
+                "status": "{status}",
+                "devMode": true,
+                "methods": ["{method}"],
+                "frequency": "{frequency}",
+                "createdAt": "2024-11-04T18:38:28.573Z",
+                "updateAt": "2024-11-04T18:38:28.573Z",
+                "__v": 0,
+                "url": "https://abacatepay.com/pay/bill_123",
+                "id": "bill_123",
+                "customerId": null,
+                "customer": null
+            }}"#
+        )
+    }
+
+    #[test]
+    fn known_values() {
+        let billing: Billing =
+            serde_json::from_str(&billing_json("PAID", "PIX", "ONE_TIME")).unwrap();
+
+        assert_eq!(billing.status, BillingStatus::PAID);
+        assert_eq!(billing.methods, vec![BillingMethods::Pix]);
+        assert_eq!(billing.frequency, BillingKind::OneTime);
+    }
+
+    #[test]
+    fn card_method() {
+        let billing: Billing =
+            serde_json::from_str(&billing_json("PAID", "CARD", "ONE_TIME")).unwrap();
+        let legacy: Billing =
+            serde_json::from_str(&billing_json("PAID", "Card", "ONE_TIME")).unwrap();
+
+        assert_eq!(billing.methods, vec![BillingMethods::Card]);
+        assert_eq!(legacy.methods, vec![BillingMethods::Card]);
+    }
+
+    #[test]
+    fn mixed_methods_serialization() {
+        let mut data = valid_request();
+        data.methods = vec![BillingMethods::Pix, BillingMethods::Card];
+
+        let json = serde_json::to_value(&data).unwrap();
+
+        assert_eq!(json["methods"], serde_json::json!(["PIX", "CARD"]));
+        assert!(data.validate().is_ok());
+    }
+
+    #[test]
+    fn unknown_values() {
+        let billing: Billing =
+            serde_json::from_str(&billing_json("ON_HOLD", "BOLETO", "SUBSCRIPTION")).unwrap();
+
+        assert_eq!(
+            billing.status,
+            BillingStatus::Unknown("ON_HOLD".to_string())
+        );
+        assert_eq!(
+            billing.methods,
+            vec![BillingMethods::Unknown("BOLETO".to_string())]
+        );
+        assert_eq!(
+            billing.frequency,
+            BillingKind::Unknown("SUBSCRIPTION".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_values_round_trip() {
+        let method = BillingMethods::Unknown("BOLETO".to_string());
+        let kind = BillingKind::Unknown("SUBSCRIPTION".to_string());
+
+        assert_eq!(serde_json::to_string(&method).unwrap(), r#""BOLETO""#);
+        assert_eq!(serde_json::to_string(&kind).unwrap(), r#""SUBSCRIPTION""#);
+    }
+
+    fn valid_request() -> CreateBillingData {
+        CreateBillingData {
+            frequency: BillingKind::OneTime,
+            methods: vec![BillingMethods::Pix],
+            products: vec![CreateBillingProduct {
+                external_id: "external_id_1".to_string(),
+                name: "Product 1".to_string(),
+                quantity: 1,
+                price: 10.0,
+                description: None,
+            }],
+            return_url: "http://localhost:3030".to_string(),
+            completion_url: "https://example.com/done?success=true".to_string(),
+            customer_id: None,
+        }
+    }
+
+    #[test]
+    fn validate_accepts_valid_request() {
+        assert!(valid_request().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_reports_every_invalid_field() {
+        let mut data = valid_request();
+        data.methods.clear();
+        data.products[0].quantity = 0;
+        data.products[0].price = -1.0;
+        data.return_url = String::new();
+        data.completion_url = "ftp://example.com".to_string();
+
+        let Err(AbacatePayError::Validation(errors)) = data.validate() else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                "methods",
+                "products[0].quantity",
+                "products[0].price",
+                "return_url",
+                "completion_url",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_checks_products() {
+        let mut data = valid_request();
+        let product = data.products[0].clone();
+        data.products = vec![
+            CreateBillingProduct {
+                price: 0.0,
+                description: Some(" ".to_string()),
+                ..product.clone()
+            },
+            CreateBillingProduct {
+                quantity: 0,
+                description: Some("a".repeat(MAX_PRODUCT_DESCRIPTION_LENGTH + 1)),
+                ..product.clone()
+            },
+            CreateBillingProduct {
+                description: Some("a".repeat(MAX_PRODUCT_DESCRIPTION_LENGTH)),
+                ..product
+            },
+        ];
+
+        let Err(AbacatePayError::Validation(errors)) = data.validate() else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                "products[0].price",
+                "products[0].description",
+                "products[1].quantity",
+                "products[1].description",
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_matches() {
+        let billing: Billing =
+            serde_json::from_str(&billing_json("PAID", "PIX", "ONE_TIME")).unwrap();
+        let created_at = billing.created_at;
+
+        assert!(BillingFilter::default().matches(&billing));
+        assert!(BillingFilter {
+            statuses: vec![BillingStatus::PENDING, BillingStatus::PAID],
+            created_from: Some(created_at),
+            created_until: Some(created_at + chrono::Duration::seconds(1)),
+            frequency: Some(BillingKind::OneTime),
+            ..BillingFilter::default()
+        }
+        .matches(&billing));
+
+        assert!(!BillingFilter {
+            statuses: vec![BillingStatus::PENDING],
+            ..BillingFilter::default()
+        }
+        .matches(&billing));
+        assert!(!BillingFilter {
+            created_until: Some(created_at),
+            ..BillingFilter::default()
+        }
+        .matches(&billing));
+        assert!(!BillingFilter {
+            customer_id: Some(CustomerId::from("cust_123")),
+            ..BillingFilter::default()
+        }
+        .matches(&billing));
+        assert!(!BillingFilter {
+            frequency: Some(BillingKind::MultiplePayments),
+            ..BillingFilter::default()
+        }
+        .matches(&billing));
+    }
+
+    #[test]
+    fn list_response_with_unknown_status() {
+        let json = format!(
+            r#"{{ "error": null, "billings": [{}, {}] }}"#,
+            billing_json("PENDING", "PIX", "ONE_TIME"),
+            billing_json("DISPUTED", "PIX", "MULTIPLE_PAYMENTS"),
+        );
+
+        let response: ListBillingResponse = serde_json::from_str(&json).unwrap();
+        let ListBillingResponse::Success { billings, .. } = response else {
+            panic!("expected a successful response");
+        };
+
+        assert_eq!(billings[0].status, BillingStatus::PENDING);
+        assert_eq!(
+            billings[1].status,
+            BillingStatus::Unknown("DISPUTED".to_string())
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+    };
+}
+
+id_type!(
+    /// Identifier of a billing, e.g. `bill_12345`.
+    BillingId
+);
+
+id_type!(
+    /// Identifier of a PIX QR code charge, e.g. `pix_char_12345`.
+    PixChargeId
+);
+
+id_type!(
+    /// Identifier of a customer, e.g. `cust_12345`.
+    CustomerId
+);
+
+id_type!(
+    /// Identifier of a refund, e.g. `refund_12345`.
+    RefundId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_transparently() {
+        let id = PixChargeId::new("pix_char_123");
+
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""pix_char_123""#);
+        assert_eq!(
+            serde_json::from_str::<PixChargeId>(r#""pix_char_123""#).unwrap(),
+            id
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let id = BillingId::from("bill_123");
+
+        assert_eq!(id.as_str(), "bill_123");
+        assert_eq!(id.to_string(), "bill_123");
+        assert_eq!(
+            CustomerId::from("cust_123".to_string()).into_inner(),
+            "cust_123"
+        );
+    }
+}
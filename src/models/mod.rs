@@ -1,2 +1,4 @@
 pub mod billing;
+pub mod ids;
 pub mod pix_charge;
+pub mod refund;
@@ -2,21 +2,43 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::billing::CustomerMetadata;
+use super::ids::PixChargeId;
+use crate::brcode::{BrCode, BrCodeError};
+use crate::error::{validation_result, AbacatePayError, FieldError};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Longest description, in characters, accepted for a PIX charge.
+pub const MAX_DESCRIPTION_LENGTH: usize = 140;
+
+/// Longest expiration, in seconds, accepted for a PIX charge (30 days).
+pub const MAX_EXPIRES_IN: u64 = 30 * 24 * 60 * 60;
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub enum PixStatus {
     PENDING,
     EXPIRED,
     CANCELLED,
     PAID,
     REFUNDED,
+    /// A status not yet known to this version of the SDK.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl PixStatus {
+    /// Whether the charge can no longer change status on its own.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            PixStatus::PAID | PixStatus::EXPIRED | PixStatus::CANCELLED | PixStatus::REFUNDED
+        )
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PixChargeData {
     pub amount: i64,
-    pub status: String,
+    pub status: PixStatus,
     pub dev_mode: bool,
     pub method: String,
     pub br_code: String,
@@ -25,7 +47,7 @@ pub struct PixChargeData {
     pub created_at: String,
     pub updated_at: String,
     pub expires_at: String,
-    pub id: String,
+    pub id: PixChargeId,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -47,6 +69,42 @@ pub struct CreatePixChargeData {
     pub customer: Option<CustomerMetadata>,
 }
 
+impl PixChargeData {
+    /// Parses and checksum-verifies `br_code`.
+    pub fn parse_br_code(&self) -> Result<BrCode, BrCodeError> {
+        BrCode::parse(&self.br_code)
+    }
+}
+
+impl CreatePixChargeData {
+    /// Checks the request locally, returning every invalid field at once.
+    pub fn validate(&self) -> Result<(), AbacatePayError> {
+        let mut errors = Vec::new();
+
+        if self.amount <= 0 {
+            errors.push(FieldError::new("amount", "must be greater than zero"));
+        }
+        if let Some(expires_in) = self.expires_in {
+            if expires_in == 0 || expires_in > MAX_EXPIRES_IN {
+                errors.push(FieldError::new(
+                    "expires_in",
+                    format!("must be between 1 and {MAX_EXPIRES_IN} seconds"),
+                ));
+            }
+        }
+        if let Some(description) = &self.description {
+            if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+                errors.push(FieldError::new(
+                    "description",
+                    format!("must be at most {MAX_DESCRIPTION_LENGTH} characters"),
+                ));
+            }
+        }
+
+        validation_result(errors)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum CheckPixStatusResponse {
@@ -74,3 +132,65 @@ pub enum PixChargeResponse {
         code: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_valid_request() {
+        let data = CreatePixChargeData {
+            amount: 1000,
+            expires_in: Some(3600),
+            description: Some("Test PIX charge".to_string()),
+            customer: None,
+        };
+
+        assert!(data.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_reports_every_invalid_field() {
+        let data = CreatePixChargeData {
+            amount: 0,
+            expires_in: Some(0),
+            description: Some("a".repeat(MAX_DESCRIPTION_LENGTH + 1)),
+            customer: None,
+        };
+
+        let Err(AbacatePayError::Validation(errors)) = data.validate() else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+
+        assert_eq!(fields, vec!["amount", "expires_in", "description"]);
+    }
+
+    #[test]
+    fn check_status_known_value() {
+        let json = r#"{ "error": null, "data": { "status": "PAID", "expiresAt": "2025-03-25T21:50:20.772Z" } }"#;
+
+        let CheckPixStatusResponse::Success { data, .. } = serde_json::from_str(json).unwrap()
+        else {
+            panic!("expected a successful response");
+        };
+
+        assert_eq!(data.status, PixStatus::PAID);
+    }
+
+    #[test]
+    fn check_status_unknown_value() {
+        let json = r#"{ "error": null, "data": { "status": "UNDER_REVIEW", "expiresAt": "2025-03-25T21:50:20.772Z" } }"#;
+
+        let CheckPixStatusResponse::Success { data, .. } = serde_json::from_str(json).unwrap()
+        else {
+            panic!("expected a successful response");
+        };
+
+        assert_eq!(data.status, PixStatus::Unknown("UNDER_REVIEW".to_string()));
+        assert_eq!(
+            serde_json::to_string(&data.status).unwrap(),
+            r#""UNDER_REVIEW""#
+        );
+    }
+}
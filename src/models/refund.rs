@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ids::RefundId;
+use crate::error::{validation_result, AbacatePayError, FieldError};
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+pub enum RefundStatus {
+    PENDING,
+    COMPLETED,
+    FAILED,
+    /// A status not yet known to this version of the SDK.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Refund {
+    pub id: RefundId,
+    /// Refunded amount in cents.
+    pub amount: i64,
+    pub status: RefundStatus,
+    pub reason: Option<String>,
+    pub dev_mode: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for refunding a billing or PIX charge identified by `Id`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRefundData<Id> {
+    pub id: Id,
+    /// Amount to refund in cents; `None` refunds the full amount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl<Id> CreateRefundData<Id> {
+    /// Checks the request locally, returning every invalid field at once.
+    pub fn validate(&self) -> Result<(), AbacatePayError> {
+        let mut errors = Vec::new();
+
+        if self.amount.is_some_and(|amount| amount <= 0) {
+            errors.push(FieldError::new("amount", "must be greater than zero"));
+        }
+        if self
+            .reason
+            .as_deref()
+            .is_some_and(|reason| reason.trim().is_empty())
+        {
+            errors.push(FieldError::new("reason", "must not be blank"));
+        }
+
+        validation_result(errors)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum RefundResponse {
+    Success {
+        error: Option<()>,
+        data: Refund,
+    },
+    Error {
+        error: String,
+        message: String,
+        code: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ids::PixChargeId;
+
+    #[test]
+    fn full_refund_omits_amount() {
+        let data = CreateRefundData {
+            id: PixChargeId::from("pix_char_123"),
+            amount: None,
+            reason: None,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            serde_json::json!({ "id": "pix_char_123" })
+        );
+        assert!(data.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_amount() {
+        let data = CreateRefundData {
+            id: PixChargeId::from("pix_char_123"),
+            amount: Some(0),
+            reason: Some(" ".to_string()),
+        };
+
+        let Err(AbacatePayError::Validation(errors)) = data.validate() else {
+            panic!("expected a validation error");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+
+        assert_eq!(fields, vec!["amount", "reason"]);
+    }
+}
@@ -0,0 +1,573 @@
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, instrument, warn};
+
+use crate::client::AbacatePay;
+use crate::error::AbacatePayError;
+use crate::ids::PixChargeId;
+use crate::pix_charge::{CheckPixStatusData, PixStatus};
+
+/// Lower bound for the delay between two checks, so a zero interval cannot hammer the API.
+const MIN_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Upper bound for [`PollOptions::multiplier`].
+const MAX_MULTIPLIER: f64 = 10.0;
+
+/// How often and for how long to poll the status of a PIX charge.
+#[derive(Debug, Clone)]
+pub struct PollOptions {
+    /// Delay before the second status check; raised to 50 ms if lower.
+    pub initial_interval: Duration,
+    /// Upper bound for the delay between two checks.
+    pub max_interval: Duration,
+    /// Factor applied to the delay after every check, clamped to `1.0..=10.0`.
+    pub multiplier: f64,
+    /// How long to keep polling after the charge's `expires_at` has passed, giving the
+    /// API time to report it as `EXPIRED`.
+    pub expiry_grace: Duration,
+    /// Optional overall limit, counted from the first check.
+    pub timeout: Option<Duration>,
+    /// Stops polling with `AbacatePayError::Cancelled` once cancelled.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            initial_interval: Duration::from_secs(2),
+            max_interval: Duration::from_secs(30),
+            multiplier: 1.5,
+            expiry_grace: Duration::from_secs(30),
+            timeout: None,
+            cancellation: None,
+        }
+    }
+}
+
+impl PollOptions {
+    pub(crate) fn first_interval(&self) -> Duration {
+        self.initial_interval
+            .clamp(MIN_INTERVAL, self.max_interval.max(MIN_INTERVAL))
+    }
+
+    pub(crate) fn next_interval(&self, interval: Duration) -> Duration {
+        let multiplier = if self.multiplier.is_nan() {
+            1.0
+        } else {
+            self.multiplier.clamp(1.0, MAX_MULTIPLIER)
+        };
+        let max_interval = self.max_interval.max(MIN_INTERVAL);
+
+        Duration::try_from_secs_f64(interval.as_secs_f64() * multiplier)
+            .unwrap_or(max_interval)
+            .clamp(MIN_INTERVAL, max_interval)
+    }
+
+    /// The instant after which polling gives up, whichever of the charge expiry (plus
+    /// grace) and the overall timeout comes first.
+    ///
+    /// `None` when neither applies, e.g. an `expiry_grace` of `Duration::MAX` and no timeout.
+    pub(crate) fn deadline(&self, started: Instant, expires_at: DateTime<Utc>) -> Option<Instant> {
+        let until_expiry = (expires_at - Utc::now()).to_std().unwrap_or_default();
+        let expiry_deadline = Instant::now()
+            .checked_add(until_expiry)
+            .and_then(|instant| instant.checked_add(self.expiry_grace));
+
+        match (expiry_deadline, self.timeout_deadline(started)) {
+            (Some(expiry), Some(timeout)) => Some(expiry.min(timeout)),
+            (expiry, timeout) => expiry.or(timeout),
+        }
+    }
+
+    /// The instant at which the optional `timeout` runs out.
+    pub(crate) fn timeout_deadline(&self, started: Instant) -> Option<Instant> {
+        self.timeout
+            .and_then(|timeout| started.checked_add(timeout))
+    }
+
+    /// Runs `future` unless the cancellation token fires first.
+    pub(crate) async fn cancellable<T>(
+        &self,
+        future: impl Future<Output = Result<T, AbacatePayError>>,
+    ) -> Result<T, AbacatePayError> {
+        match &self.cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(AbacatePayError::Cancelled),
+                result = future => result,
+            },
+            None => future.await,
+        }
+    }
+
+    pub(crate) async fn sleep(&self, delay: Duration) -> Result<(), AbacatePayError> {
+        self.cancellable(async {
+            tokio::time::sleep(delay).await;
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl AbacatePay {
+    /// Polls `pixQrCode/check` until the charge reaches a terminal status.
+    ///
+    /// Polling stops early once the charge's `expires_at` (plus `expiry_grace`) or the
+    /// optional `timeout` has passed; the last status seen is returned in that case, so
+    /// callers should check `status.is_terminal()` on the result.
+    #[instrument(skip(self, options))]
+    pub async fn wait_for_pix_payment(
+        &self,
+        id: PixChargeId,
+        options: PollOptions,
+    ) -> Result<CheckPixStatusData, AbacatePayError> {
+        let started = Instant::now();
+        let mut interval = options.first_interval();
+
+        loop {
+            let data = options
+                .cancellable(self.check_pix_status(id.clone()).build())
+                .await?;
+
+            if data.status.is_terminal() {
+                debug!(status = ?data.status, "PIX charge reached a terminal status");
+                return Ok(data);
+            }
+
+            let deadline = options.deadline(started, data.expires_at);
+            let now = Instant::now();
+            let delay = match deadline {
+                Some(deadline) if now >= deadline => {
+                    debug!(status = ?data.status, "Stopped waiting for PIX payment at deadline");
+                    return Ok(data);
+                }
+                Some(deadline) => interval.min(deadline - now),
+                None => interval,
+            };
+
+            options.sleep(delay).await?;
+            interval = options.next_interval(interval);
+        }
+    }
+}
+
+struct WatchState {
+    id: PixChargeId,
+    options: PollOptions,
+    started: Instant,
+    deadline: Option<Instant>,
+    interval: Duration,
+    error_interval: Duration,
+    next_delay: Option<Duration>,
+    last_status: Option<PixStatus>,
+    last_error: Option<AbacatePayError>,
+    retries: u32,
+    done: bool,
+}
+
+impl AbacatePay {
+    /// Streams the status of a PIX charge, yielding an item only when it changes.
+    ///
+    /// The stream ends after yielding a terminal status, once the charge's `expires_at`
+    /// (plus `expiry_grace`) or the optional `timeout` has passed, or when the cancellation
+    /// token fires. Transient failures (network errors, 5xx, 429) are retried with backoff;
+    /// if the deadline passes while retrying, the last such error is yielded before the
+    /// stream ends. Any other error is yielded and ends the stream.
+    pub fn watch_pix_charge(
+        &self,
+        id: PixChargeId,
+        options: PollOptions,
+    ) -> impl Stream<Item = Result<CheckPixStatusData, AbacatePayError>> + '_ {
+        let state = WatchState {
+            id,
+            interval: options.first_interval(),
+            error_interval: options.first_interval(),
+            options,
+            started: Instant::now(),
+            deadline: None,
+            next_delay: None,
+            last_status: None,
+            last_error: None,
+            retries: 0,
+            done: false,
+        };
+
+        futures::stream::unfold(state, move |mut state| async move {
+            while !state.done {
+                if let Some(delay) = state.next_delay.take() {
+                    state.options.sleep(delay).await.ok()?;
+                }
+                let now = Instant::now();
+                let timeout_deadline = state.options.timeout_deadline(state.started);
+                if state
+                    .deadline
+                    .into_iter()
+                    .chain(timeout_deadline)
+                    .any(|deadline| now >= deadline)
+                {
+                    state.done = true;
+                    return state.last_error.take().map(|e| (Err(e), state));
+                }
+
+                let result = state
+                    .options
+                    .cancellable(
+                        self.check_pix_status(state.id.clone())
+                            .resend_count(state.retries)
+                            .build(),
+                    )
+                    .await;
+
+                match result {
+                    Ok(data) => {
+                        state.retries = 0;
+                        state.last_error = None;
+                        state.error_interval = state.options.first_interval();
+                        let deadline = state.options.deadline(state.started, data.expires_at);
+                        state.deadline = deadline;
+
+                        let now = Instant::now();
+                        if data.status.is_terminal() || deadline.is_some_and(|d| now >= d) {
+                            state.done = true;
+                        } else {
+                            state.next_delay = Some(
+                                deadline.map_or(state.interval, |d| state.interval.min(d - now)),
+                            );
+                            state.interval = state.options.next_interval(state.interval);
+                        }
+
+                        if state.last_status.as_ref() != Some(&data.status) {
+                            debug!(status = ?data.status, "PIX charge status changed");
+                            state.last_status = Some(data.status.clone());
+                            return Some((Ok(data), state));
+                        }
+                    }
+                    Err(AbacatePayError::Cancelled) => return None,
+                    Err(e) if e.is_retryable() => {
+                        warn!(error = %e, "Transient error while watching PIX charge, retrying");
+                        state.retries += 1;
+                        state.last_error = Some(e);
+                        state.next_delay = Some(match timeout_deadline {
+                            Some(timeout_deadline) => state
+                                .error_interval
+                                .min(timeout_deadline.saturating_duration_since(Instant::now())),
+                            None => state.error_interval,
+                        });
+                        state.error_interval = state.options.next_interval(state.error_interval);
+                    }
+                    Err(e) => {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn status_body(status: &str, expires_at: DateTime<Utc>) -> serde_json::Value {
+        serde_json::json!({
+            "error": null,
+            "data": { "status": status, "expiresAt": expires_at },
+        })
+    }
+
+    fn fast_options() -> PollOptions {
+        PollOptions {
+            initial_interval: Duration::from_millis(5),
+            max_interval: Duration::from_millis(20),
+            ..PollOptions::default()
+        }
+    }
+
+    #[test]
+    fn next_interval_is_capped() {
+        let options = PollOptions {
+            initial_interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(3),
+            multiplier: 2.0,
+            ..PollOptions::default()
+        };
+
+        let second = options.next_interval(options.initial_interval);
+        let third = options.next_interval(second);
+
+        assert_eq!(second, Duration::from_secs(2));
+        assert_eq!(third, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn next_interval_survives_bad_options() {
+        let options = PollOptions {
+            initial_interval: Duration::ZERO,
+            max_interval: Duration::MAX,
+            multiplier: f64::INFINITY,
+            ..PollOptions::default()
+        };
+
+        assert_eq!(options.first_interval(), MIN_INTERVAL);
+        assert_eq!(
+            options.next_interval(Duration::from_secs(1)),
+            Duration::from_secs(10)
+        );
+        assert_eq!(options.next_interval(Duration::MAX), Duration::MAX);
+
+        let options = PollOptions {
+            max_interval: Duration::ZERO,
+            multiplier: f64::NAN,
+            ..options
+        };
+        assert_eq!(options.first_interval(), MIN_INTERVAL);
+        assert_eq!(options.next_interval(Duration::ZERO), MIN_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn returns_once_paid() {
+        let server = MockServer::start().await;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .and(query_param("id", "pix_char_123"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("PENDING", expires_at)),
+            )
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(status_body("PAID", expires_at)))
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let data = client
+            .wait_for_pix_payment(PixChargeId::from("pix_char_123"), fast_options())
+            .await
+            .unwrap();
+
+        assert_eq!(data.status, PixStatus::PAID);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_after_expiry() {
+        let server = MockServer::start().await;
+        let expires_at = Utc::now() - chrono::Duration::seconds(5);
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("PENDING", expires_at)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let options = PollOptions {
+            expiry_grace: Duration::ZERO,
+            ..fast_options()
+        };
+        let data = client
+            .wait_for_pix_payment(PixChargeId::from("pix_char_123"), options)
+            .await
+            .unwrap();
+
+        assert_eq!(data.status, PixStatus::PENDING);
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unbounded_expiry_grace() {
+        let server = MockServer::start().await;
+        let expires_at = Utc::now() - chrono::Duration::seconds(5);
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("PENDING", expires_at)),
+            )
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("EXPIRED", expires_at)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let options = PollOptions {
+            expiry_grace: Duration::MAX,
+            ..fast_options()
+        };
+        let data = client
+            .wait_for_pix_payment(PixChargeId::from("pix_char_123"), options)
+            .await
+            .unwrap();
+
+        assert_eq!(data.status, PixStatus::EXPIRED);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn can_be_cancelled() {
+        let server = MockServer::start().await;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("PENDING", expires_at)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let token = CancellationToken::new();
+        let options = PollOptions {
+            cancellation: Some(token.clone()),
+            ..fast_options()
+        };
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            token.cancel();
+        });
+        let result = client
+            .wait_for_pix_payment(PixChargeId::from("pix_char_123"), options)
+            .await;
+
+        assert!(matches!(result, Err(AbacatePayError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn watch_yields_only_status_changes() {
+        let server = MockServer::start().await;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("PENDING", expires_at)),
+            )
+            .up_to_n_times(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(status_body("PAID", expires_at)))
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let statuses: Vec<_> = client
+            .watch_pix_charge(PixChargeId::from("pix_char_123"), fast_options())
+            .map(|item| item.unwrap().status)
+            .collect()
+            .await;
+
+        assert_eq!(statuses, vec![PixStatus::PENDING, PixStatus::PAID]);
+    }
+
+    #[tokio::test]
+    async fn watch_retries_transient_errors() {
+        let server = MockServer::start().await;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(status_body("EXPIRED", expires_at)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let items: Vec<_> = client
+            .watch_pix_charge(PixChargeId::from("pix_char_123"), fast_options())
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].as_ref().unwrap().status, PixStatus::EXPIRED);
+    }
+
+    #[tokio::test]
+    async fn watch_honors_timeout_while_retrying() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let options = PollOptions {
+            timeout: Some(Duration::from_millis(300)),
+            ..fast_options()
+        };
+        let items: Vec<_> = tokio::time::timeout(
+            Duration::from_secs(5),
+            client
+                .watch_pix_charge(PixChargeId::from("pix_char_123"), options)
+                .collect(),
+        )
+        .await
+        .expect("stream should end once the timeout passes");
+
+        assert_eq!(items.len(), 1);
+        assert!(matches!(
+            items[0],
+            Err(AbacatePayError::UnexpectedResponse { status, .. })
+                if status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+        ));
+    }
+
+    #[tokio::test]
+    async fn watch_ends_on_client_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/pixQrCode/check"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": "Not found",
+                "message": "PIX charge not found",
+                "code": "NOT_FOUND",
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AbacatePay::new("my-key!".to_string()).with_base_url(server.uri());
+        let items: Vec<_> = client
+            .watch_pix_charge(PixChargeId::from("pix_char_123"), fast_options())
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0], Err(AbacatePayError::ApiError { .. })));
+    }
+}
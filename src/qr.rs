@@ -0,0 +1,187 @@
+//! Rendering of PIX BR Codes as QR code images (requires the `qrcode` feature).
+
+use std::io::Cursor;
+
+use base64::Engine;
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::render::{svg, unicode};
+use qrcode::{EcLevel, QrCode};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum QrError {
+    #[error("Failed to encode QR code: {0}")]
+    Encode(#[from] qrcode::types::QrError),
+
+    #[error("Failed to write image: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("Invalid base64 image: {0}")]
+    Base64(#[from] base64::DecodeError),
+}
+
+/// How much of the symbol can be damaged and still be read; higher levels make denser codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorCorrection {
+    /// Recovers about 7% of the symbol.
+    Low,
+    /// Recovers about 15% of the symbol.
+    #[default]
+    Medium,
+    /// Recovers about 25% of the symbol.
+    Quartile,
+    /// Recovers about 30% of the symbol.
+    High,
+}
+
+impl From<ErrorCorrection> for EcLevel {
+    fn from(level: ErrorCorrection) -> Self {
+        match level {
+            ErrorCorrection::Low => EcLevel::L,
+            ErrorCorrection::Medium => EcLevel::M,
+            ErrorCorrection::Quartile => EcLevel::Q,
+            ErrorCorrection::High => EcLevel::H,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QrOptions {
+    /// Size of one module (black or white square) in pixels, for PNG and SVG output.
+    pub module_size: u32,
+    /// Whether to surround the code with the blank margin scanners expect.
+    pub quiet_zone: bool,
+    pub error_correction: ErrorCorrection,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            module_size: 8,
+            quiet_zone: true,
+            error_correction: ErrorCorrection::default(),
+        }
+    }
+}
+
+fn encode(br_code: &str, options: &QrOptions) -> Result<QrCode, QrError> {
+    Ok(QrCode::with_error_correction_level(
+        br_code,
+        options.error_correction.into(),
+    )?)
+}
+
+/// Renders `br_code` as PNG bytes.
+pub fn render_png(br_code: &str, options: &QrOptions) -> Result<Vec<u8>, QrError> {
+    let image = encode(br_code, options)?
+        .render::<Luma<u8>>()
+        .module_dimensions(options.module_size, options.module_size)
+        .quiet_zone(options.quiet_zone)
+        .build();
+
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(image).write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(png)
+}
+
+/// Renders `br_code` as an SVG document.
+pub fn render_svg(br_code: &str, options: &QrOptions) -> Result<String, QrError> {
+    Ok(encode(br_code, options)?
+        .render::<svg::Color<'_>>()
+        .module_dimensions(options.module_size, options.module_size)
+        .quiet_zone(options.quiet_zone)
+        .build())
+}
+
+/// Renders `br_code` with Unicode half blocks, two modules per character, for terminals.
+/// `module_size` is ignored.
+pub fn render_unicode(br_code: &str, options: &QrOptions) -> Result<String, QrError> {
+    Ok(encode(br_code, options)?
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .quiet_zone(options.quiet_zone)
+        .build())
+}
+
+/// Decodes `PixChargeData::br_code_base64` into raw image bytes, accepting either plain
+/// base64 or a `data:image/png;base64,` URI.
+pub fn decode_br_code_base64(data: &str) -> Result<Vec<u8>, QrError> {
+    let encoded = match data.split_once(";base64,") {
+        Some((prefix, encoded)) if prefix.starts_with("data:") => encoded,
+        _ => data,
+    };
+    Ok(base64::engine::general_purpose::STANDARD.decode(encoded.trim())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BR_CODE: &str = "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-4266554400005204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D";
+
+    #[test]
+    fn png_has_expected_size() {
+        let options = QrOptions {
+            module_size: 4,
+            quiet_zone: false,
+            error_correction: ErrorCorrection::Low,
+        };
+        let width = encode(BR_CODE, &options).unwrap().width() as u32;
+
+        let png = render_png(BR_CODE, &options).unwrap();
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG"));
+        assert_eq!(image.width(), width * 4);
+    }
+
+    #[test]
+    fn svg_and_unicode_output() {
+        let options = QrOptions::default();
+
+        let svg = render_svg(BR_CODE, &options).unwrap();
+        let text = render_unicode(BR_CODE, &options).unwrap();
+
+        assert!(svg.contains("<svg"));
+        assert!(text.contains('█') || text.contains('▀') || text.contains('▄'));
+    }
+
+    #[test]
+    fn higher_error_correction_is_denser() {
+        let low = encode(
+            BR_CODE,
+            &QrOptions {
+                error_correction: ErrorCorrection::Low,
+                ..QrOptions::default()
+            },
+        )
+        .unwrap();
+        let high = encode(
+            BR_CODE,
+            &QrOptions {
+                error_correction: ErrorCorrection::High,
+                ..QrOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert!(high.width() > low.width());
+    }
+
+    #[test]
+    fn decodes_data_uri_and_plain_base64() {
+        let png = render_png(BR_CODE, &QrOptions::default()).unwrap();
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&png);
+
+        assert_eq!(decode_br_code_base64(&encoded).unwrap(), png);
+        assert_eq!(
+            decode_br_code_base64(&format!("data:image/png;base64,{encoded}")).unwrap(),
+            png
+        );
+        assert!(matches!(
+            decode_br_code_base64("not base64!"),
+            Err(QrError::Base64(_))
+        ));
+    }
+}
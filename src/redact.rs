@@ -0,0 +1,200 @@
+use serde::Serialize;
+use serde_json::error::Category;
+use serde_json::Value;
+
+/// Placeholder logged in place of sensitive values.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// JSON keys whose values are never logged, compared case-insensitively and ignoring `_`.
+const SENSITIVE_KEYS: &[&str] = &[
+    "taxid",
+    "email",
+    "cellphone",
+    "phone",
+    "name",
+    "brcode",
+    "brcodebase64",
+    "apikey",
+    "secret",
+    "token",
+    "authorization",
+];
+
+/// How request and response bodies are written to the debug log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyLogging {
+    /// Bodies are not logged.
+    Off,
+    /// Bodies are logged with customer data, BR Codes and credentials masked.
+    #[default]
+    Redacted,
+    /// Bodies are logged verbatim, including personal data. Only for local debugging.
+    Full,
+}
+
+impl BodyLogging {
+    /// The loggable form of a serializable request body, if bodies are logged at all.
+    pub(crate) fn request<T: Serialize>(self, body: &T) -> Option<String> {
+        let value = serde_json::to_value(body).ok()?;
+        self.value(value)
+    }
+
+    /// The loggable form of a raw response body, if bodies are logged at all.
+    pub(crate) fn response(self, body: &str) -> Option<String> {
+        match self {
+            BodyLogging::Off => None,
+            BodyLogging::Full => Some(body.to_string()),
+            BodyLogging::Redacted => match serde_json::from_str(body) {
+                Ok(value) => self.value(value),
+                Err(_) => Some(format!("<{} bytes of non-JSON body>", body.len())),
+            },
+        }
+    }
+
+    /// The form of a response body kept in an error, which callers tend to print or log:
+    /// verbatim with [`BodyLogging::Full`], redacted otherwise.
+    pub(crate) fn error_body(self, body: &str) -> String {
+        match self {
+            BodyLogging::Full => body.to_string(),
+            _ => BodyLogging::Redacted.response(body).unwrap_or_default(),
+        }
+    }
+
+    /// Describes why a response body could not be decoded. Unless bodies are logged in full,
+    /// only the position is kept, as serde's messages quote the offending values.
+    pub(crate) fn parse_error(self, error: &serde_json::Error) -> String {
+        match self {
+            BodyLogging::Full => error.to_string(),
+            _ => {
+                let kind = match error.classify() {
+                    Category::Io => "I/O error",
+                    Category::Syntax => "invalid JSON",
+                    Category::Data => "unexpected value",
+                    Category::Eof => "unexpected end of body",
+                };
+                match error.line() {
+                    0 => kind.to_string(),
+                    line => format!("{kind} at line {line} column {}", error.column()),
+                }
+            }
+        }
+    }
+
+    fn value(self, mut value: Value) -> Option<String> {
+        match self {
+            BodyLogging::Off => None,
+            BodyLogging::Full => Some(value.to_string()),
+            BodyLogging::Redacted => {
+                redact(&mut value);
+                Some(value.to_string())
+            }
+        }
+    }
+}
+
+/// Masks the value of every sensitive key in `value`, at any depth.
+pub(crate) fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn is_sensitive(key: &str) -> bool {
+    let key = key.replace('_', "").to_ascii_lowercase();
+    SENSITIVE_KEYS.contains(&key.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn masks_sensitive_fields_at_any_depth() {
+        let mut body = json!({
+            "data": {
+                "id": "pix_char_123",
+                "amount": 1000,
+                "brCode": "00020101021226950014br.gov.bcb.pix",
+                "customer": {
+                    "metadata": {
+                        "name": "Ana Silva",
+                        "email": "ana@example.com",
+                        "cellphone": "(11) 4002-8922",
+                        "tax_id": "123.456.789-01"
+                    }
+                },
+                "customerId": null
+            },
+            "items": [{ "email": "b@example.com" }]
+        });
+
+        redact(&mut body);
+
+        assert_eq!(
+            body,
+            json!({
+                "data": {
+                    "id": "pix_char_123",
+                    "amount": 1000,
+                    "brCode": REDACTED,
+                    "customer": {
+                        "metadata": {
+                            "name": REDACTED,
+                            "email": REDACTED,
+                            "cellphone": REDACTED,
+                            "tax_id": REDACTED
+                        }
+                    },
+                    "customerId": null
+                },
+                "items": [{ "email": REDACTED }]
+            })
+        );
+    }
+
+    #[test]
+    fn logging_levels() {
+        let body = r#"{"email":"ana@example.com"}"#;
+
+        assert_eq!(BodyLogging::Off.response(body), None);
+        assert_eq!(BodyLogging::Full.response(body).as_deref(), Some(body));
+        assert_eq!(
+            BodyLogging::Redacted.response(body).as_deref(),
+            Some(r#"{"email":"[REDACTED]"}"#)
+        );
+        assert_eq!(
+            BodyLogging::Redacted.response("<html>").as_deref(),
+            Some("<6 bytes of non-JSON body>")
+        );
+    }
+
+    #[test]
+    fn error_bodies_and_messages() {
+        let body = r#"{"email":"ana@example.com"}"#;
+        let error = serde_json::from_str::<Vec<i64>>(r#"["ana@example.com"]"#).unwrap_err();
+
+        assert_eq!(
+            BodyLogging::Off.error_body(body),
+            r#"{"email":"[REDACTED]"}"#
+        );
+        assert_eq!(BodyLogging::Full.error_body(body), body);
+        assert_eq!(
+            BodyLogging::Redacted.parse_error(&error),
+            "unexpected value at line 1 column 18"
+        );
+        assert!(BodyLogging::Full
+            .parse_error(&error)
+            .contains("ana@example.com"));
+    }
+}
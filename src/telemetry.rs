@@ -0,0 +1,104 @@
+//! W3C trace context propagation (requires the `opentelemetry` feature).
+
+use opentelemetry::propagation::Injector;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::RequestBuilder;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Adds the headers of the globally installed propagator (e.g. `traceparent`) for the
+/// current span, so the API call joins the caller's distributed trace.
+pub(crate) fn inject_trace_context(request: RequestBuilder) -> RequestBuilder {
+    let context = tracing::Span::current().context();
+    let mut headers = HeaderMap::new();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers));
+    });
+    request.headers(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AbacatePay;
+    use opentelemetry::trace::{SpanKind, TracerProvider};
+    use opentelemetry::{KeyValue, Value};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+    use tracing_subscriber::layer::SubscriberExt;
+    use wiremock::matchers::{header_regex, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn injects_traceparent_and_records_http_attributes() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(async {
+                    let server = MockServer::start().await;
+                    Mock::given(method("GET"))
+                        .and(path("/billing/list"))
+                        .and(header_regex(
+                            "traceparent",
+                            "^00-[0-9a-f]{32}-[0-9a-f]{16}-01$",
+                        ))
+                        .respond_with(
+                            ResponseTemplate::new(200)
+                                .set_body_string(r#"{"billings":[],"error":null}"#),
+                        )
+                        .expect(1)
+                        .mount(&server)
+                        .await;
+
+                    let client = AbacatePay::new("abc_dev_123").with_base_url(server.uri());
+                    client.list_billings().await.unwrap();
+                })
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans
+            .iter()
+            .find(|span| span.name == "GET billing/list")
+            .expect("request span");
+        let attribute = |key: &str| {
+            span.attributes
+                .iter()
+                .find(|attribute| attribute.key.as_str() == key)
+                .map(|attribute| attribute.value.clone())
+        };
+
+        assert_eq!(span.span_kind, SpanKind::Client);
+        assert_eq!(attribute("http.request.method"), Some(Value::from("GET")));
+        assert_eq!(attribute("url.template"), Some(Value::from("billing/list")));
+        assert_eq!(
+            attribute("http.response.status_code"),
+            Some(Value::I64(200))
+        );
+        assert_eq!(attribute("server.address"), Some(Value::from("127.0.0.1")));
+        assert_eq!(attribute("http.request.resend_count"), None);
+        assert!(!span
+            .attributes
+            .contains(&KeyValue::new("otel.status_code", "ERROR")));
+    }
+}
@@ -0,0 +1,269 @@
+//! Verification and parsing of AbacatePay webhook deliveries (requires the `webhook` feature).
+//!
+//! Each delivery is a JSON `POST` whose raw body is signed with HMAC-SHA256; the signature is
+//! sent base64-encoded in the `X-Webhook-Signature` header.
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::billing::BillingStatus;
+use crate::ids::{BillingId, PixChargeId};
+use crate::pix_charge::PixStatus;
+
+/// Header carrying the base64 HMAC-SHA256 signature of the body.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("Webhook signature is not valid base64 or hex")]
+    MalformedSignature,
+
+    #[error("Webhook signature does not match the payload")]
+    InvalidSignature,
+
+    #[error("Failed to parse webhook payload: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Checks `signature` (base64 or hex) against the HMAC-SHA256 of `body` keyed with `secret`.
+///
+/// `body` must be the raw bytes received, before any JSON re-encoding.
+pub fn verify_signature(body: &[u8], signature: &str, secret: &str) -> Result<(), WebhookError> {
+    let signature = signature.trim();
+    let expected = base64::engine::general_purpose::STANDARD
+        .decode(signature)
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .or_else(|| decode_hex(signature))
+        .ok_or(WebhookError::MalformedSignature)?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    mac.verify_slice(&expected)
+        .map_err(|_| WebhookError::InvalidSignature)
+}
+
+/// Computes the base64 signature AbacatePay would send for `body`, e.g. to test a handler.
+pub fn sign(body: &[u8], secret: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() != 64 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A webhook delivery.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawWebhookEvent")]
+pub struct WebhookEvent {
+    pub id: String,
+    pub dev_mode: bool,
+    pub kind: WebhookEventKind,
+}
+
+impl WebhookEvent {
+    /// Parses a delivery body. Verify its signature first with [`verify_signature`].
+    pub fn parse(body: &[u8]) -> Result<Self, WebhookError> {
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WebhookEventKind {
+    /// `billing.paid`: a billing or PIX QR code charge was paid.
+    BillingPaid(PaymentEventData),
+    /// `withdraw.done`
+    WithdrawDone(WithdrawEventData),
+    /// `withdraw.failed`
+    WithdrawFailed(WithdrawEventData),
+    /// An event not yet known to this version of the SDK.
+    Unknown {
+        event: String,
+        data: serde_json::Value,
+    },
+}
+
+impl WebhookEventKind {
+    /// The event name as sent by the API, e.g. `billing.paid`.
+    pub fn name(&self) -> &str {
+        match self {
+            WebhookEventKind::BillingPaid(_) => "billing.paid",
+            WebhookEventKind::WithdrawDone(_) => "withdraw.done",
+            WebhookEventKind::WithdrawFailed(_) => "withdraw.failed",
+            WebhookEventKind::Unknown { event, .. } => event,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawWebhookEvent {
+    id: String,
+    event: String,
+    dev_mode: bool,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+impl TryFrom<RawWebhookEvent> for WebhookEvent {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawWebhookEvent) -> Result<Self, Self::Error> {
+        let kind = match raw.event.as_str() {
+            "billing.paid" => WebhookEventKind::BillingPaid(serde_json::from_value(raw.data)?),
+            "withdraw.done" => WebhookEventKind::WithdrawDone(serde_json::from_value(raw.data)?),
+            "withdraw.failed" => {
+                WebhookEventKind::WithdrawFailed(serde_json::from_value(raw.data)?)
+            }
+            _ => WebhookEventKind::Unknown {
+                event: raw.event,
+                data: raw.data,
+            },
+        };
+
+        Ok(WebhookEvent {
+            id: raw.id,
+            dev_mode: raw.dev_mode,
+            kind,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentEventData {
+    pub payment: WebhookPayment,
+    /// Present when a billing was paid.
+    pub billing: Option<WebhookBilling>,
+    /// Present when a PIX QR code charge was paid.
+    pub pix_qr_code: Option<WebhookPixQrCode>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayment {
+    /// Paid amount in cents.
+    pub amount: i64,
+    /// Fee in cents.
+    pub fee: i64,
+    pub method: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookBilling {
+    pub id: BillingId,
+    pub amount: i64,
+    pub status: BillingStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPixQrCode {
+    pub id: PixChargeId,
+    pub amount: i64,
+    pub status: PixStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawEventData {
+    pub transaction: WithdrawTransaction,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawTransaction {
+    pub id: String,
+    pub status: String,
+    /// Withdrawn amount in cents.
+    pub amount: i64,
+    pub platform_fee: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+
+    fn billing_paid() -> &'static [u8] {
+        br#"{
+            "id": "log_12345",
+            "event": "billing.paid",
+            "devMode": true,
+            "data": {
+                "payment": { "amount": 1000, "fee": 80, "method": "PIX" },
+                "billing": { "id": "bill_123", "amount": 1000, "status": "PAID" }
+            }
+        }"#
+    }
+
+    #[test]
+    fn verifies_base64_and_hex_signatures() {
+        let body = billing_paid();
+        let signature = sign(body, SECRET);
+        let hex: String = base64::engine::general_purpose::STANDARD
+            .decode(&signature)
+            .unwrap()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        assert!(verify_signature(body, &signature, SECRET).is_ok());
+        assert!(verify_signature(body, &hex, SECRET).is_ok());
+    }
+
+    #[test]
+    fn rejects_wrong_signatures() {
+        let body = billing_paid();
+        let signature = sign(body, "another-secret");
+
+        assert!(matches!(
+            verify_signature(body, &signature, SECRET),
+            Err(WebhookError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verify_signature(body, "not a signature", SECRET),
+            Err(WebhookError::MalformedSignature)
+        ));
+    }
+
+    #[test]
+    fn parses_billing_paid() {
+        let event = WebhookEvent::parse(billing_paid()).unwrap();
+
+        assert_eq!(event.id, "log_12345");
+        assert!(event.dev_mode);
+        let WebhookEventKind::BillingPaid(data) = event.kind else {
+            panic!("expected billing.paid");
+        };
+        assert_eq!(data.payment.amount, 1000);
+        assert_eq!(data.billing.unwrap().status, BillingStatus::PAID);
+        assert!(data.pix_qr_code.is_none());
+    }
+
+    #[test]
+    fn parses_unknown_event() {
+        let body =
+            br#"{ "id": "log_1", "event": "subscription.renewed", "devMode": false, "data": {} }"#;
+
+        let event = WebhookEvent::parse(body).unwrap();
+
+        assert_eq!(event.kind.name(), "subscription.renewed");
+        assert!(matches!(event.kind, WebhookEventKind::Unknown { .. }));
+    }
+}